@@ -1,128 +1,418 @@
-use consume::ConsumeCommand;
-use go::GoCommand;
-use godmode::GodmodeCommand;
-use help::HelpCommand;
-use hit::HitCommand;
-use look::LookCommand;
-use put::PutCommand;
-use quit::QuitCommand;
-use search::SearchCommand;
-use see::SeeCommand;
-use see_player::SeePlayerCommand;
-use take::TakeCommand;
-use unknown::UnknownCommand;
-use wait::WaitCommand;
-use wear::WearCommand;
-
-use crate::{room::Direction, Game};
+use crate::{
+    messages::{Language, Message},
+    room::Direction,
+    Game,
+};
 
 mod consume;
 mod go;
 mod godmode;
 mod help;
 mod hit;
+mod leaderboard;
+mod load;
 mod look;
+mod look_in;
+mod open;
 mod put;
 mod quit;
+mod save;
 mod search;
 mod see;
 mod see_player;
+mod suggestions;
 mod take;
-mod unknown;
+mod unlock;
 mod wait;
 mod wear;
 
-pub(crate) enum Commands {
-    Help(HelpCommand),
-    Look(LookCommand),
-    Search(SearchCommand),
-    Go(GoCommand),
-    Take(TakeCommand), // object
-    Put(PutCommand),   // object
-    See(SeeCommand),   // object/enemy
-    SeePlayer(SeePlayerCommand),
-    Hit(HitCommand),   //enemy
-    Wear(WearCommand), // object weapon/armor
-    Wait(WaitCommand),
-    Consume(ConsumeCommand), // object consumable
-    Godmode(GodmodeCommand),
-    Quit(QuitCommand),
-    Unknown(UnknownCommand),
-}
-
 pub(crate) trait Command {
     fn execute(&self, game: &mut Game);
 }
 
-impl Command for Commands {
-    fn execute(&self, game: &mut Game) {
-        match self {
-            Commands::Help(cmd) => cmd.execute(game),
-            Commands::Look(cmd) => cmd.execute(game),
-            Commands::Search(cmd) => cmd.execute(game),
-            Commands::Go(cmd) => cmd.execute(game),
-            Commands::Take(cmd) => cmd.execute(game),
-            Commands::Put(cmd) => cmd.execute(game),
-            Commands::See(cmd) => cmd.execute(game),
-            Commands::SeePlayer(cmd) => cmd.execute(game),
-            Commands::Hit(cmd) => cmd.execute(game),
-            Commands::Wear(cmd) => cmd.execute(game),
-            Commands::Wait(cmd) => cmd.execute(game),
-            Commands::Consume(cmd) => cmd.execute(game),
-            Commands::Godmode(cmd) => cmd.execute(game),
-            Commands::Quit(cmd) => cmd.execute(game),
-            Commands::Unknown(cmd) => cmd.execute(game),
+/// Runs a command with everything the player typed after the verb.
+pub type CommandHandler = fn(&mut Game, &str);
+
+/// Lists what the argument of a command can be right now, e.g. the exits of the room for `Go`.
+pub type CommandSuggestions = fn(&Game) -> Vec<String>;
+
+/// Whether the enemies get to act after a command, `Game::execute` gives them their turn
+/// once the handler returns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Turn {
+    Free,
+    /// The enemies in the room attack the player.
+    EnemiesAttack,
+    /// The enemies in the room attack the player, then every enemy may move to another room.
+    EnemiesAttackAndMove,
+}
+
+impl Turn {
+    /// What happens after the command, in `language`.
+    pub fn describe(&self, language: Language) -> &'static str {
+        let message = match self {
+            Turn::Free => Message::TurnFree,
+            Turn::EnemiesAttack => Message::TurnEnemiesAttack,
+            Turn::EnemiesAttackAndMove => Message::TurnEnemiesAttackAndMove,
+        };
+        message.text_in(language)
+    }
+}
+
+/// What a command expects after its verb, named for the help text, e.g. `Required("Object")`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Argument {
+    None,
+    Required(&'static str),
+    Optional(&'static str),
+}
+
+/// A command as the player sees it.
+#[derive(Clone, Copy)]
+pub struct CommandSpec {
+    /// Name shown in the help, e.g. "Take".
+    pub name: &'static str,
+    /// Everything the player can type to start the command, in lowercase. Verbs can span
+    /// several words, e.g. "pick up".
+    pub verbs: &'static [&'static str],
+    pub argument: Argument,
+    /// One line describing what the command does, in English. The built-in commands have
+    /// translations in the message catalog, see `help_text`.
+    pub help: &'static str,
+    pub turn: Turn,
+    /// Complete inputs shown by `Help <command>`.
+    pub examples: &'static [&'static str],
+    pub suggestions: Option<CommandSuggestions>,
+    pub handler: CommandHandler,
+}
+
+impl CommandSpec {
+    /// The command with its argument, e.g. "Take <Object>" or "Leaderboard [Count]", with the
+    /// argument of a built-in command named in `language`.
+    pub fn usage(&self, language: Language) -> String {
+        let translated = |argument| {
+            Message::argument_name(argument).map_or(argument, |name| name.text_in(language))
+        };
+        match self.argument {
+            Argument::None => self.name.to_string(),
+            Argument::Required(argument) => format!("{} <{}>", self.name, translated(argument)),
+            Argument::Optional(argument) => format!("{} [{}]", self.name, translated(argument)),
+        }
+    }
+
+    /// `help` in `language`. A command that replaces a built-in one under the same name keeps
+    /// its own help.
+    pub fn help_text(&self, language: Language) -> &'static str {
+        match Message::command_help(self.name) {
+            Some(message) if message.text_in(Language::English) == self.help => {
+                message.text_in(language)
+            }
+            _ => self.help,
+        }
+    }
+}
+
+/// All commands the game understands, in the order `Help` lists them.
+pub(crate) struct CommandRegistry {
+    commands: Vec<CommandSpec>,
+}
+
+impl CommandRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            commands: vec![
+                help::COMMAND,
+                look::COMMAND,
+                look_in::COMMAND,
+                search::COMMAND,
+                go::COMMAND,
+                unlock::COMMAND,
+                open::COMMAND,
+                take::COMMAND,
+                put::COMMAND,
+                see::COMMAND,
+                see_player::COMMAND,
+                hit::COMMAND,
+                wear::COMMAND,
+                wait::COMMAND,
+                consume::COMMAND,
+                godmode::COMMAND,
+                save::COMMAND,
+                load::COMMAND,
+                leaderboard::COMMAND,
+                quit::COMMAND,
+            ],
+        }
+    }
+
+    /// Adds `command`. Its verbs take precedence over those of commands registered before it.
+    pub(crate) fn register(&mut self, command: CommandSpec) {
+        self.commands.push(command);
+    }
+
+    pub(crate) fn commands(&self) -> &[CommandSpec] {
+        &self.commands
+    }
+
+    /// The command `input` names, as in `Help LookIn`: its name, ignoring case and spaces, or
+    /// the command its verbs start.
+    pub(crate) fn find(&self, input: &str) -> Option<&CommandSpec> {
+        let squashed = |text: &str| text.split_whitespace().collect::<String>().to_lowercase();
+        self.commands
+            .iter()
+            .rev()
+            .find(|command| squashed(command.name) == squashed(input))
+            .or_else(|| self.parse(input).map(|(command, _)| command))
+    }
+
+    /// Finds the command `input` starts with and returns it with the words after the verb.
+    pub(crate) fn parse(&self, input: &str) -> Option<(&CommandSpec, String)> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+
+        let mut found: Option<(usize, &CommandSpec)> = None;
+        for command in &self.commands {
+            for verb in command.verbs {
+                let verb_words: Vec<&str> = verb.split(' ').collect();
+                let matches = words.len() >= verb_words.len()
+                    && verb_words
+                        .iter()
+                        .zip(&words)
+                        .all(|(verb_word, word)| word.eq_ignore_ascii_case(verb_word));
+                if matches && found.is_none_or(|(length, _)| verb_words.len() >= length) {
+                    found = Some((verb_words.len(), command));
+                }
+            }
         }
+        if let Some((length, command)) = found {
+            return Some((command, words[length..].join(" ")));
+        }
+
+        // A direction on its own is short for Go, e.g. "n" or "noord", also when Go was
+        // registered again. Verbs come first, so a command can still use such a word.
+        let [word] = words.as_slice() else {
+            return None;
+        };
+        let go = self
+            .commands
+            .iter()
+            .rev()
+            .find(|command| command.name == "Go");
+        go.filter(|_| Direction::from_str(word) != Direction::None)
+            .map(|go| (go, word.to_string()))
+    }
+}
+
+/// Splits the arguments of a command at the last of `words`, e.g. "dolk in kist" at "in"
+/// gives "dolk" and "kist". Without such a word all of `args` comes first.
+fn split_at_word(args: &str, words: &[&str]) -> (String, Option<String>) {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    match parts
+        .iter()
+        .rposition(|part| words.contains(&part.to_lowercase().as_str()))
+    {
+        Some(position) => (
+            parts[..position].join(" "),
+            Some(parts[position + 1..].join(" ")),
+        ),
+        None => (args.to_string(), None),
     }
 }
 
-pub(crate) fn parse_input_to_command(input: &str) -> Commands {
-    let args: Vec<&str> = input.split_whitespace().collect();
-    match args[0] {
-        "Help" => Commands::Help(HelpCommand {}),
-        "Quit" => Commands::Quit(QuitCommand {}),
-        "Go" => Commands::Go(GoCommand {
-            direction: parse_args_to_direction(&args),
-        }),
-        "Look" => Commands::Look(LookCommand {}),
-        "Wait" => Commands::Wait(WaitCommand {}),
-        "Search" => Commands::Search(SearchCommand {}),
-        "See" => Commands::See(SeeCommand {
-            enemy_name: parse_args_to_item_name(&args),
-        }),
-        "SeePlayer" => Commands::SeePlayer(SeePlayerCommand {}),
-        "Take" => Commands::Take(TakeCommand {
-            item: parse_args_to_item_name(&args),
-        }),
-        "Put" => Commands::Put(PutCommand {
-            item: parse_args_to_item_name(&args),
-        }),
-        "Wear" => Commands::Wear(WearCommand {
-            item: parse_args_to_item_name(&args),
-        }),
-        "Consume" => Commands::Consume(ConsumeCommand {
-            item: parse_args_to_item_name(&args),
-        }),
-        "Hit" => Commands::Hit(HitCommand {
-            name: parse_args_to_item_name(&args),
-        }),
-        "Godmode" => Commands::Godmode(GodmodeCommand {}),
-        _ => Commands::Unknown(UnknownCommand {}),
+/// Splits a leading number off the arguments of a command, e.g. "3 klein levenselixer"
+/// gives 3 and "klein levenselixer". A number on its own is left alone, it picks an item.
+fn split_count(args: &str) -> (Option<u32>, String) {
+    match args.trim().split_once(char::is_whitespace) {
+        Some((number, rest)) => match number.parse::<u32>() {
+            Ok(count) if count > 0 => (Some(count), rest.trim().to_string()),
+            _ => (None, args.to_string()),
+        },
+        None => (None, args.to_string()),
     }
 }
 
-pub(crate) fn parse_args_to_direction(args: &Vec<&str>) -> Direction {
-    if args.len() != 2 {
-        Direction::None
-    } else {
-        Direction::from_str(args[1])
+/// Splits a line into the commands chained in it with `;` or "then",
+/// e.g. "Take dolk; Wear dolk then Go North". Empty commands are dropped.
+pub(crate) fn split_input(input: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    for part in input.split(';') {
+        let mut command: Vec<&str> = Vec::new();
+        for word in part.split_whitespace() {
+            if word.eq_ignore_ascii_case("then") || word.eq_ignore_ascii_case("dan") {
+                commands.push(command.join(" "));
+                command.clear();
+            } else {
+                command.push(word);
+            }
+        }
+        commands.push(command.join(" "));
     }
+    commands.retain(|command| !command.is_empty());
+    commands
 }
 
-pub(crate) fn parse_args_to_item_name(args: &Vec<&str>) -> String {
-    if args.len() < 2 {
-        "To little commands arguments".to_string()
-    } else {
-        args[1..].join(" ")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Option<(&'static str, String)> {
+        let registry = CommandRegistry::new();
+        registry
+            .parse(input)
+            .map(|(command, args)| (command.name, args))
+    }
+
+    fn parses_to_go(input: &str, expected: Direction) -> bool {
+        matches!(parse(input), Some(("Go", args)) if Direction::from_str(&args) == expected)
+    }
+
+    fn parses_to(input: &str, expected: &str) -> bool {
+        matches!(parse(input), Some((name, _)) if name == expected)
+    }
+
+    #[test]
+    fn test_verbs_and_directions_ignore_case() {
+        assert!(parses_to_go("go north", Direction::North));
+        assert!(parses_to_go("GO South", Direction::South));
+        assert!(parses_to("LOOK", "Look"));
+    }
+
+    #[test]
+    fn test_direction_shortcuts() {
+        assert!(parses_to_go("n", Direction::North));
+        assert!(parses_to_go("Go w", Direction::West));
+        assert!(parses_to_go("zuid", Direction::South));
+        assert!(parses_to_go("ga oost", Direction::East));
+        assert!(parses_to_go("u", Direction::Up));
+        assert!(parses_to_go("ga omlaag", Direction::Down));
+        assert!(parses_to_go("NE", Direction::NorthEast));
+        assert!(parses_to_go("zuidwest", Direction::SouthWest));
+    }
+
+    #[test]
+    fn test_no_verb_is_a_direction() {
+        for command in CommandRegistry::new().commands() {
+            for verb in command.verbs {
+                assert_eq!(Direction::from_str(verb), Direction::None, "{}", verb);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verbs_come_before_directions() {
+        let mut registry = CommandRegistry::new();
+        registry.register(CommandSpec {
+            name: "Dig",
+            verbs: &["d"],
+            ..see::COMMAND
+        });
+        assert_eq!(
+            registry.parse("d").map(|(command, _)| command.name),
+            Some("Dig")
+        );
+        assert_eq!(
+            registry.parse("u").map(|(command, _)| command.name),
+            Some("Go")
+        );
+    }
+
+    #[test]
+    fn test_synonyms_keep_the_object_name() {
+        assert_eq!(
+            parse("pick up kleine buidel"),
+            Some(("Take", "kleine buidel".to_string()))
+        );
+        assert!(parses_to("get dolk", "Take"));
+        assert!(parses_to("drop dolk", "Put"));
+        assert!(parses_to("kill Goblin", "Hit"));
+        assert!(parses_to("i", "SeePlayer"));
+        assert!(parses_to("l", "Look"));
+        assert!(parses_to("pak dolk", "Take"));
+        assert!(parses_to("sla Goblin", "Hit"));
+    }
+
+    #[test]
+    fn test_look_in_and_containers() {
+        assert_eq!(parse("look in kist"), Some(("LookIn", "kist".to_string())));
+        assert_eq!(
+            split_at_word("dolk, speer in leren tas", &["in"]),
+            ("dolk, speer".to_string(), Some("leren tas".to_string()))
+        );
+        assert_eq!(
+            split_at_word("all potions", &["from", "uit"]),
+            ("all potions".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_split_count() {
+        assert_eq!(
+            split_count("3 klein levenselixer"),
+            (Some(3), "klein levenselixer".to_string())
+        );
+        assert_eq!(split_count("2"), (None, "2".to_string()));
+        assert_eq!(split_count("dolk 2"), (None, "dolk 2".to_string()));
+        assert_eq!(split_count("0 dolk"), (None, "0 dolk".to_string()));
+    }
+
+    #[test]
+    fn test_split_chained_commands() {
+        assert_eq!(
+            split_input("Take dolk; Wear dolk then go north ;"),
+            ["Take dolk", "Wear dolk", "go north"]
+        );
+        assert!(split_input("   ").is_empty());
+    }
+
+    #[test]
+    fn test_empty_input_is_not_a_command() {
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn test_unknown_verb() {
+        assert_eq!(parse("dance"), None);
+    }
+
+    #[test]
+    fn test_registered_command_overrides_verbs() {
+        let mut registry = CommandRegistry::new();
+        registry.register(CommandSpec {
+            name: "Dance",
+            verbs: &["dance", "look"],
+            argument: Argument::None,
+            help: "Dance",
+            turn: Turn::Free,
+            examples: &[],
+            suggestions: None,
+            handler: |_, _| {},
+        });
+        assert_eq!(registry.parse("look").unwrap().0.name, "Dance");
+        assert_eq!(registry.parse("l").unwrap().0.name, "Look");
+    }
+
+    #[test]
+    fn test_usage_of_every_command_can_be_typed() {
+        let registry = CommandRegistry::new();
+        for command in registry.commands() {
+            let usage = command.usage(Language::English);
+            let typed = usage.replace(['<', '>', '[', ']'], "");
+            let parsed = registry.parse(&typed).map(|(parsed, _)| parsed.name);
+            assert_eq!(parsed, Some(command.name), "{}", usage);
+            let name = usage.split([' ', '<', '[']).next().unwrap();
+            assert_eq!(
+                registry.find(name).map(|found| found.name),
+                Some(command.name)
+            );
+        }
+        assert_eq!(registry.find("look in").unwrap().name, "LookIn");
+        assert_eq!(registry.find("see player").unwrap().name, "SeePlayer");
+    }
+
+    #[test]
+    fn test_built_in_help_is_in_the_catalog() {
+        for command in CommandRegistry::new().commands() {
+            let message = Message::command_help(command.name).unwrap();
+            assert_eq!(message.text_in(Language::English), command.help);
+        }
     }
 }
@@ -1,15 +1,61 @@
-use crate::Game;
+use crate::{
+    name_resolver::{item_candidates, ItemSelection},
+    objects::ItemCategory,
+    Game,
+};
 
-use super::Command;
+use super::{split_count, suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct ConsumeCommand {
     pub(crate) item: String,
+    /// How many to drink of a stack, one when `None` unless the player said "all".
+    pub(crate) count: Option<u32>,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Consume",
+    verbs: &["consume", "drink", "eat", "quaff", "eet", "gebruik"],
+    argument: Argument::Required("Object"),
+    help: "Drink potions from your backpack, also 'all' or a list",
+    turn: Turn::Free,
+    examples: &[
+        "Consume klein levenselixer",
+        "Consume 2 klein levenselixer",
+        "Consume all potions",
+    ],
+    suggestions: Some(suggestions::potion_names),
+    handler: |game, args| {
+        let (count, item) = split_count(args);
+        ConsumeCommand { item, count }.execute(game)
+    },
+};
+
 impl Command for ConsumeCommand {
     fn execute(&self, game: &mut Game) {
-        let world = game.get_world();
-        let player = world.get_player_mut();
-        player.consume_potion(self.item.as_str());
+        // Only potions can be consumed, so "Consume all" leaves weapons and armor alone.
+        let player = game.get_world().get_player();
+        let potions = player
+            .backpack_items()
+            .iter()
+            .filter(|item| item.category() == ItemCategory::Potion);
+        let candidates = item_candidates(potions);
+        let all = matches!(ItemSelection::parse(&self.item), ItemSelection::All { .. });
+        for item_name in game.choose_items(&self.item, &candidates) {
+            let world = game.get_world();
+            let stack = world
+                .get_player()
+                .backpack_items()
+                .iter()
+                .find(|item| item.name() == item_name)
+                .map_or(1, |item| item.count());
+            let count = match self.count {
+                Some(count) => count.min(stack),
+                None if all => stack,
+                None => 1,
+            };
+            for _ in 0..count {
+                world.player_consume_potion(item_name.as_str());
+            }
+        }
     }
 }
@@ -1,25 +1,44 @@
-use crate::{console_output, room::Direction, Game};
+use crate::{console::console::Style, console_output, message, Game};
 
-use super::Command;
+use super::{suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct GoCommand {
-    pub(crate) direction: Direction,
+    pub(crate) exit: String,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Go",
+    verbs: &["go", "walk", "ga", "loop"],
+    argument: Argument::Required("Direction"),
+    help: "Walk through an exit, e.g. North, n, up or the name of an exit",
+    turn: Turn::EnemiesAttack,
+    examples: &["Go North", "n", "ga zuid", "Go up", "Go put"],
+    suggestions: Some(suggestions::exit_directions),
+    handler: |game, args| {
+        GoCommand {
+            exit: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
 impl Command for GoCommand {
     fn execute(&self, game: &mut Game) {
-        let current_room = game.get_current_room();
-        let new_room = current_room.check_direction(&self.direction);
-
-        if new_room != 0 {
-            let world = game.get_world();
-            world.goto_next_room(new_room);
-            console_output!("Going to {new_room}\n");
-        } else {
-            console_output!("Wrong goto direction\n");
+        let console = game.console().clone();
+        match game.choose_exit(&self.exit) {
+            Some(exit) => match game.world().exit_blocked(&exit) {
+                Some(reason) => {
+                    console_output!(console, "{}", console.styled(Style::Warning, reason));
+                }
+                None => game.get_world().goto_next_room(exit.id()),
+            },
+            None => {
+                console_output!(
+                    console,
+                    "{}",
+                    console.styled(Style::Warning, message!(console, WrongDirection))
+                );
+            }
         }
-
-        let world = game.get_world();
-        world.enemies_attack();
     }
 }
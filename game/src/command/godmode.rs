@@ -1,14 +1,24 @@
 use crate::Game;
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
 pub struct GodmodeCommand {}
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Godmode",
+    verbs: &["godmode"],
+    argument: Argument::None,
+    help: "Toggle invulnerability",
+    turn: Turn::Free,
+    examples: &["Godmode"],
+    suggestions: None,
+    handler: |game, _args| GodmodeCommand {}.execute(game),
+};
+
 impl Command for GodmodeCommand {
     fn execute(&self, game: &mut Game) {
         let world = game.get_world();
         let player = world.get_player_mut();
         player.toggle_godmode();
     }
-
 }
@@ -1,29 +1,92 @@
-use crate::{console_output, Game};
+use crate::{console_message, console_output, Game};
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
-pub struct HelpCommand {}
+pub struct HelpCommand {
+    pub(crate) command: String,
+}
 
-const COMMANDS: [&str; 15] = [
-    "Help",
-    "Look",
-    "Search",
-    "Go <Direction>",
-    "Take <Object>",
-    "Put <Object>",
-    "See <Object>",
-    "SeePlayer",
-    "Hit <Enemy>",
-    "Wear <Object>",
-    "Wait",
-    "Consume <Object>",
-    "Godmode",
-    "Quit",
-    "Unknown",
-];
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Help",
+    verbs: &["help", "hulp", "?"],
+    argument: Argument::Optional("Command"),
+    help: "Show the commands you can use, or everything about one of them",
+    turn: Turn::Free,
+    examples: &["Help", "Help Take"],
+    suggestions: None,
+    handler: |game, args| {
+        HelpCommand {
+            command: args.to_string(),
+        }
+        .execute(game)
+    },
+};
 
 impl Command for HelpCommand {
-    fn execute(&self, _game: &mut Game) {
-        console_output!("{:?}\n", COMMANDS);
+    fn execute(&self, game: &mut Game) {
+        if self.command.is_empty() {
+            show_commands(game);
+            return;
+        }
+        match game.command_registry().find(&self.command) {
+            Some(command) => show_command(game, command),
+            None => {
+                console_message!(game.console(), NoSuchCommand, self.command);
+            }
+        }
+    }
+}
+
+fn show_commands(game: &Game) {
+    let console = game.console();
+    let language = console.language();
+    console_message!(console, CommandsHeader);
+    for command in game.command_registry().commands() {
+        console_output!(
+            console,
+            "  {:<22}{}\n",
+            command.usage(language),
+            command.help_text(language)
+        );
+    }
+    console_message!(console, ChainingHint);
+
+    console_message!(console, RightNowHeader);
+    for command in game.command_registry().commands() {
+        let Some(suggestions) = command.suggestions else {
+            continue;
+        };
+        let suggestions = suggestions(game);
+        if !suggestions.is_empty() {
+            console_output!(
+                console,
+                "  {:<12}{}\n",
+                command.name,
+                suggestions.join(", ")
+            );
+        }
+    }
+}
+
+fn show_command(game: &Game, command: &CommandSpec) {
+    let console = game.console();
+    let language = console.language();
+    console_message!(console, Usage, command.usage(language));
+    console_output!(console, "{}\n", command.help_text(language));
+    console_message!(console, AlsoVerbs, command.verbs.join(", "));
+    console_output!(console, "{}\n", command.turn.describe(language));
+    if !command.examples.is_empty() {
+        console_message!(console, ExamplesHeader);
+        for example in command.examples {
+            console_output!(console, "  {}\n", example);
+        }
+    }
+    if let Some(suggestions) = command.suggestions {
+        let suggestions = suggestions(game);
+        if suggestions.is_empty() {
+            console_message!(console, NothingToUseOn);
+        } else {
+            console_message!(console, RightNow, suggestions.join(", "));
+        }
     }
 }
@@ -1,19 +1,35 @@
 use crate::Game;
 
-use super::Command;
+use super::{suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct HitCommand {
     pub(crate) name: String,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Hit",
+    verbs: &["hit", "attack", "kill", "sla", "val aan"],
+    argument: Argument::Required("Enemy"),
+    help: "Attack an enemy in the room",
+    turn: Turn::EnemiesAttackAndMove,
+    examples: &["Hit Goblin", "Hit second rat"],
+    suggestions: Some(suggestions::enemy_names),
+    handler: |game, args| {
+        HitCommand {
+            name: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
 impl Command for HitCommand {
     fn execute(&self, game: &mut Game) {
+        let candidates = game.get_world().enemy_names_in_current_room();
+        let Some(name) = game.choose_name(&self.name, &candidates) else {
+            return;
+        };
         let world = game.get_world();
-        let player = world.get_player();
-        let player_dmg = player.hit();
-        world.player_attack_enemy(player_dmg, self.name.as_str());
-
-        world.enemies_attack();
-        world.enemies_move();
+        let player_dmg = world.player_hit();
+        world.player_attack_enemy(player_dmg, name.as_str());
     }
 }
@@ -0,0 +1,32 @@
+use crate::{
+    leaderboard::{show_leaderboard, DEFAULT_LEADERBOARD_SIZE},
+    Game,
+};
+
+use super::{Argument, Command, CommandSpec, Turn};
+
+pub(crate) struct LeaderboardCommand {
+    pub(crate) count: usize,
+}
+
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Leaderboard",
+    verbs: &["leaderboard", "scores", "ranglijst"],
+    argument: Argument::Optional("Count"),
+    help: "Show the best scores",
+    turn: Turn::Free,
+    examples: &["Leaderboard", "Leaderboard 5"],
+    suggestions: None,
+    handler: |game, args| {
+        LeaderboardCommand {
+            count: args.parse().unwrap_or(DEFAULT_LEADERBOARD_SIZE),
+        }
+        .execute(game)
+    },
+};
+
+impl Command for LeaderboardCommand {
+    fn execute(&self, game: &mut Game) {
+        show_leaderboard(game.console(), game.database_path(), self.count);
+    }
+}
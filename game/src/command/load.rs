@@ -0,0 +1,39 @@
+use crate::{console_message, save::load_world, Game};
+
+use super::{Argument, Command, CommandSpec, Turn};
+
+pub(crate) struct LoadCommand {
+    pub(crate) slot: String,
+}
+
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Load",
+    verbs: &["load", "laad"],
+    argument: Argument::Required("Slot"),
+    help: "Continue a game saved in a slot",
+    turn: Turn::Free,
+    examples: &["Load kasteel"],
+    suggestions: None,
+    handler: |game, args| {
+        LoadCommand {
+            slot: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
+impl Command for LoadCommand {
+    fn execute(&self, game: &mut Game) {
+        let console = game.console().clone();
+        match load_world(game.save_directory(), self.slot.as_str()) {
+            Ok(world) => {
+                game.replace_world(world);
+                console_message!(console, GameLoaded, self.slot);
+                game.get_world().show();
+            }
+            Err(error) => {
+                console_message!(console, CouldNotLoad, error.describe(console.language()));
+            }
+        }
+    }
+}
@@ -1,9 +1,20 @@
 use crate::Game;
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
 pub struct LookCommand {}
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Look",
+    verbs: &["look", "l", "kijk"],
+    argument: Argument::None,
+    help: "Describe the room, its items, exits and enemies",
+    turn: Turn::Free,
+    examples: &["Look", "l"],
+    suggestions: None,
+    handler: |game, _args| LookCommand {}.execute(game),
+};
+
 impl Command for LookCommand {
     fn execute(&self, game: &mut Game) {
         let world = game.get_world();
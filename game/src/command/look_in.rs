@@ -0,0 +1,56 @@
+use crate::{
+    console::console::Style, console_message, console_output, message, presentation::item_list,
+    Game,
+};
+
+use super::{suggestions, Argument, Command, CommandSpec, Turn};
+
+pub(crate) struct LookInCommand {
+    pub(crate) container: String,
+}
+
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "LookIn",
+    verbs: &["lookin", "look in", "kijk in"],
+    argument: Argument::Required("Container"),
+    help: "Show what is in a container",
+    turn: Turn::Free,
+    examples: &["Look in kist"],
+    suggestions: Some(suggestions::container_names),
+    handler: |game, args| {
+        LookInCommand {
+            container: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
+impl Command for LookInCommand {
+    fn execute(&self, game: &mut Game) {
+        let console = game.console().clone();
+        let Some(name) = game.choose_container(&self.container) else {
+            console_output!(
+                console,
+                "{}",
+                console.styled(
+                    Style::Warning,
+                    message!(console, NoContainer, self.container)
+                )
+            );
+            return;
+        };
+        let Some(container) = game.world().container(&name) else {
+            return;
+        };
+        if container.lock().is_some() {
+            console_message!(console, ContainerLocked, name);
+        } else {
+            console_message!(
+                console,
+                ContainerContents,
+                name,
+                item_list(console.language(), container.contents())
+            );
+        }
+    }
+}
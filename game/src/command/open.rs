@@ -0,0 +1,78 @@
+use crate::{
+    console::console::Style,
+    console_output, message,
+    name_resolver::{resolve_name, Resolution},
+    Game,
+};
+
+use super::{
+    look_in::LookInCommand, split_at_word, suggestions, unlock, unlock::UnlockCommand, Argument,
+    Command, CommandSpec, Turn,
+};
+
+pub(crate) struct OpenCommand {
+    /// A container, or an exit of the room when no container has this name.
+    pub(crate) target: String,
+    pub(crate) key: Option<String>,
+}
+
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Open",
+    verbs: &["open", "maak open"],
+    argument: Argument::Required("Container"),
+    help: "Open a container to see what is in it, or a locked exit",
+    turn: Turn::EnemiesAttack,
+    examples: &[
+        "Open kist",
+        "Open kist with hellebaard",
+        "Open north with dolk",
+    ],
+    suggestions: Some(suggestions::container_names),
+    handler: |game, args| {
+        let (target, key) = split_at_word(args, &["with", "met"]);
+        OpenCommand { target, key }.execute(game)
+    },
+};
+
+impl Command for OpenCommand {
+    fn execute(&self, game: &mut Game) {
+        let names = game.world().container_names();
+        if matches!(resolve_name(&self.target, &names), Resolution::NotFound) {
+            let room = game.get_current_room();
+            let is_exit = room.check_direction(&self.target).is_some()
+                || !matches!(
+                    resolve_name(&self.target, &room.exit_names()),
+                    Resolution::NotFound
+                );
+            if is_exit {
+                UnlockCommand {
+                    target: self.target.clone(),
+                    key: self.key.clone(),
+                }
+                .execute(game);
+            } else {
+                let console = game.console();
+                console_output!(
+                    console,
+                    "{}",
+                    console.styled(Style::Warning, message!(console, NoContainer, self.target))
+                );
+            }
+            return;
+        }
+        // Several containers match and the player did not pick one.
+        let Some(container) = game.choose_container(&self.target) else {
+            return;
+        };
+
+        unlock::unlock_container(game, &container, self.key.as_deref());
+        // When the container stays locked, unlocking it has already told the player why.
+        let locked = game
+            .world()
+            .container(&container)
+            .is_some_and(|found| found.lock().is_some());
+        if !locked {
+            LookInCommand { container }.execute(game);
+        }
+    }
+}
@@ -1,19 +1,116 @@
-use crate::Game;
+use crate::{
+    console::console::Style, console_message, console_output, message,
+    name_resolver::item_candidates, Game,
+};
 
-use super::Command;
+use super::{split_at_word, split_count, suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct PutCommand {
     pub(crate) item: String,
+    /// Put the items in this container instead of in the room.
+    pub(crate) container: Option<String>,
+    /// How many to put down of a stack, all of it when `None`.
+    pub(crate) count: Option<u32>,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Put",
+    verbs: &["put", "drop", "leg", "leg neer"],
+    argument: Argument::Required("Object"),
+    help: "Drop carried items in the room, also 'all', a list or 'in' a container",
+    turn: Turn::Free,
+    examples: &[
+        "Put dolk",
+        "Put all except langzwaard",
+        "Put all potions in tas",
+        "Put 3 klein levenselixer",
+    ],
+    suggestions: Some(suggestions::carried_item_names),
+    handler: |game, args| {
+        let (item, container) = split_at_word(args, &["in", "into"]);
+        let (count, item) = split_count(&item);
+        PutCommand {
+            item,
+            container,
+            count,
+        }
+        .execute(game)
+    },
+};
+
 impl Command for PutCommand {
     fn execute(&self, game: &mut Game) {
-        let world = game.get_world();
-        let player = world.get_player_mut();
+        if let Some(container) = &self.container {
+            put_in(game, &self.item, container, self.count);
+            return;
+        }
+
+        let console = game.console().clone();
+        let candidates = item_candidates(game.get_world().get_player().carried_items());
+        for item_name in game.choose_items(&self.item, &candidates) {
+            let world = game.get_world();
+            let player = world.get_player_mut();
+
+            if let Some(item) = player.remove_item(&console, item_name.as_str(), self.count) {
+                let current_room = world.get_current_room_mut();
+                current_room.insert_item(item);
+            }
+        }
+    }
+}
+
+fn put_in(game: &mut Game, items: &str, container: &str, count: Option<u32>) {
+    let console = game.console().clone();
+    let Some(container_name) = game.choose_container(container) else {
+        console_output!(
+            console,
+            "{}",
+            console.styled(Style::Warning, message!(console, NoContainer, container))
+        );
+        return;
+    };
+    if game
+        .world()
+        .container(&container_name)
+        .is_some_and(|found| found.lock().is_some())
+    {
+        console_message!(console, ContainerLocked, container_name);
+        return;
+    }
 
-        if let Some(item) = player.remove_item(self.item.as_str()) {
-            let current_room = world.get_current_room_mut();
-            current_room.insert_item(item);
+    let candidates = item_candidates(game.world().get_player().carried_items());
+    for item_name in game.choose_items(items, &candidates) {
+        // The container has to stay reachable once the item is taken off the player, so it
+        // cannot be the item or be inside it.
+        let holds_container = game
+            .world()
+            .get_player()
+            .carried_items()
+            .iter()
+            .any(|item| item.name() == item_name && item.find_container(&container_name).is_some());
+        if holds_container {
+            console_output!(
+                console,
+                "{}",
+                console.styled(
+                    Style::Warning,
+                    message!(console, ContainerInItself, item_name)
+                )
+            );
+            continue;
+        }
+        let world = game.get_world();
+        if world.container(&container_name).is_none() {
+            return;
+        }
+        if let Some(item) = world
+            .get_player_mut()
+            .remove_item(&console, &item_name, count)
+        {
+            if let Some(found) = world.container_mut(&container_name) {
+                console_message!(console, ItemPutIn, item.label(), container_name);
+                found.insert(item);
+            }
         }
     }
 }
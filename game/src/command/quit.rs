@@ -1,12 +1,23 @@
-use crate::{console_output, Game};
+use crate::{console_message, Game};
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
 pub struct QuitCommand {}
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Quit",
+    verbs: &["quit", "exit", "stop"],
+    argument: Argument::None,
+    help: "End the game",
+    turn: Turn::Free,
+    examples: &["Quit"],
+    suggestions: None,
+    handler: |game, _args| QuitCommand {}.execute(game),
+};
+
 impl Command for QuitCommand {
     fn execute(&self, game: &mut Game) {
+        console_message!(game.console(), EndGame);
         game.quit_game();
-        console_output!("End Game\n");
     }
 }
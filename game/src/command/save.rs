@@ -0,0 +1,38 @@
+use crate::{console_message, save::save_world, Game};
+
+use super::{Argument, Command, CommandSpec, Turn};
+
+pub(crate) struct SaveCommand {
+    pub(crate) slot: String,
+}
+
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Save",
+    verbs: &["save", "bewaar"],
+    argument: Argument::Required("Slot"),
+    help: "Save the game in a slot",
+    turn: Turn::Free,
+    examples: &["Save kasteel"],
+    suggestions: None,
+    handler: |game, args| {
+        SaveCommand {
+            slot: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
+impl Command for SaveCommand {
+    fn execute(&self, game: &mut Game) {
+        let console = game.console().clone();
+        let directory = game.save_directory().to_path_buf();
+        match save_world(game.get_world(), &directory, self.slot.as_str()) {
+            Ok(path) => {
+                console_message!(console, GameSaved, path.display());
+            }
+            Err(error) => {
+                console_message!(console, CouldNotSave, error.describe(console.language()));
+            }
+        }
+    }
+}
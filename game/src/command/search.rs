@@ -1,14 +1,27 @@
-use crate::Game;
+use crate::{console_message, Game};
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
 pub struct SearchCommand {}
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Search",
+    verbs: &["search", "zoek"],
+    argument: Argument::None,
+    help: "Search the room for hidden items and exits",
+    turn: Turn::EnemiesAttack,
+    examples: &["Search"],
+    suggestions: None,
+    handler: |game, _args| SearchCommand {}.execute(game),
+};
+
 impl Command for SearchCommand {
     fn execute(&self, game: &mut Game) {
-        let world = game.get_world();
-        let current_room = world.get_current_room_mut();
+        let console = game.console().clone();
+        let current_room = game.get_world().get_current_room_mut();
         current_room.move_invis_to_visible_items();
-        world.enemies_attack();
+        for exit in current_room.reveal_hidden_exits() {
+            console_message!(console, HiddenExitFound, exit.label(console.language()));
+        }
     }
 }
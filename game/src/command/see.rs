@@ -1,14 +1,34 @@
 use crate::Game;
 
-use super::Command;
+use super::{suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct SeeCommand {
     pub(crate) enemy_name: String,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "See",
+    verbs: &["see", "examine", "x", "bekijk"],
+    argument: Argument::Required("Enemy"),
+    help: "Look at an enemy, a dead one drops its items",
+    turn: Turn::Free,
+    examples: &["See Grote Rat", "See second rat"],
+    suggestions: Some(suggestions::enemy_names),
+    handler: |game, args| {
+        SeeCommand {
+            enemy_name: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
 impl Command for SeeCommand {
     fn execute(&self, game: &mut Game) {
+        let candidates = game.get_world().enemy_names_in_current_room();
+        let Some(enemy_name) = game.choose_name(&self.enemy_name, &candidates) else {
+            return;
+        };
         let world = game.get_world();
-        world.move_enemy_items_to_current_room(self.enemy_name.as_str());
+        world.move_enemy_items_to_current_room(enemy_name.as_str());
     }
 }
@@ -1,13 +1,24 @@
 use crate::Game;
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
 pub struct SeePlayerCommand {}
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "SeePlayer",
+    verbs: &["seeplayer", "see player", "inventory", "i", "inventaris"],
+    argument: Argument::None,
+    help: "Show your health, gold, equipment and backpack",
+    turn: Turn::Free,
+    examples: &["SeePlayer", "i"],
+    suggestions: None,
+    handler: |game, _args| SeePlayerCommand {}.execute(game),
+};
+
 impl Command for SeePlayerCommand {
     fn execute(&self, game: &mut Game) {
         let world = game.get_world();
         let player = world.get_player();
-        player.show();
+        player.show(world.console());
     }
 }
@@ -0,0 +1,58 @@
+//! What the argument of a command can be right now, for `Help`.
+
+use crate::{objects::ItemCategory, Game};
+
+pub(crate) fn exit_directions(game: &Game) -> Vec<String> {
+    let language = game.console().language();
+    game.get_current_room()
+        .visible_exits()
+        .map(|exit| exit.label(language))
+        .collect()
+}
+
+pub(crate) fn locked_exits(game: &Game) -> Vec<String> {
+    let language = game.console().language();
+    game.get_current_room()
+        .visible_exits()
+        .filter(|exit| exit.lock().is_some())
+        .map(|exit| exit.label(language))
+        .collect()
+}
+
+pub(crate) fn visible_item_names(game: &Game) -> Vec<String> {
+    game.get_current_room()
+        .visible_items()
+        .iter()
+        .map(|item| item.name().to_string())
+        .collect()
+}
+
+pub(crate) fn carried_item_names(game: &Game) -> Vec<String> {
+    game.world().get_player().carried_item_names()
+}
+
+pub(crate) fn container_names(game: &Game) -> Vec<String> {
+    game.world().container_names()
+}
+
+pub(crate) fn enemy_names(game: &Game) -> Vec<String> {
+    game.world().enemy_names_in_current_room()
+}
+
+pub(crate) fn equipment_names(game: &Game) -> Vec<String> {
+    backpack_names(game, &[ItemCategory::Weapon, ItemCategory::Armor])
+}
+
+pub(crate) fn potion_names(game: &Game) -> Vec<String> {
+    backpack_names(game, &[ItemCategory::Potion])
+}
+
+fn backpack_names(game: &Game, categories: &[ItemCategory]) -> Vec<String> {
+    game.world()
+        .get_player()
+        .backpack_items()
+        .iter()
+        .filter(|item| categories.contains(&item.category()))
+        .map(|item| item.name().to_string())
+        .collect()
+}
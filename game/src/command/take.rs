@@ -1,18 +1,132 @@
-use crate::Game;
+use crate::{
+    console::console::Style, console_message, console_output, message,
+    name_resolver::item_candidates, Game,
+};
 
-use super::Command;
+use super::{split_at_word, split_count, suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct TakeCommand {
     pub(crate) item: String,
+    /// Take the items out of this container instead of from the room.
+    pub(crate) container: Option<String>,
+    /// How many to take of a stack, all of it when `None`.
+    pub(crate) count: Option<u32>,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Take",
+    verbs: &["take", "get", "pick up", "pak", "pak op", "neem"],
+    argument: Argument::Required("Object"),
+    help: "Pick up items in the room, also 'all', a list or 'from' a container",
+    turn: Turn::Free,
+    examples: &[
+        "Take klein levenselixer",
+        "Take all potions",
+        "Take langzwaard, speer",
+        "Take all from kist",
+        "Take 2 klein levenselixer",
+    ],
+    suggestions: Some(suggestions::visible_item_names),
+    handler: |game, args| {
+        let (item, container) = split_at_word(args, &["from", "uit"]);
+        let (count, item) = split_count(&item);
+        TakeCommand {
+            item,
+            container,
+            count,
+        }
+        .execute(game)
+    },
+};
+
 impl Command for TakeCommand {
     fn execute(&self, game: &mut Game) {
+        if let Some(container) = &self.container {
+            take_from(game, &self.item, container, self.count);
+            return;
+        }
+
+        let console = game.console().clone();
+        let candidates = item_candidates(game.get_current_room().visible_items());
+        for item_name in game.choose_items(&self.item, &candidates) {
+            let world = game.get_world();
+            let too_heavy = world
+                .get_current_room()
+                .visible_items()
+                .iter()
+                .find(|item| item.name() == item_name)
+                .is_some_and(|item| !world.get_player().can_carry(&console, item, self.count));
+            if too_heavy {
+                continue;
+            }
+            let current_room = world.get_current_room_mut();
+            if let Some(new_item) = current_room.move_item(&console, item_name.as_str(), self.count)
+            {
+                world.fill_player_backpack(new_item);
+            }
+        }
+    }
+}
+
+fn take_from(game: &mut Game, items: &str, container: &str, count: Option<u32>) {
+    let console = game.console().clone();
+    let Some(container_name) = game.choose_container(container) else {
+        console_output!(
+            console,
+            "{}",
+            console.styled(Style::Warning, message!(console, NoContainer, container))
+        );
+        return;
+    };
+    let Some(found) = game.world().container(&container_name) else {
+        return;
+    };
+    if found.lock().is_some() {
+        console_message!(console, ContainerLocked, container_name);
+        return;
+    }
+
+    // Taking things out of a container in the backpack does not add to what the player carries.
+    let is_carried = game
+        .world()
+        .get_player()
+        .backpack_items()
+        .iter()
+        .any(|item| item.find_container(&container_name).is_some());
+    let candidates = item_candidates(found.contents());
+    for item_name in game.choose_items(items, &candidates) {
         let world = game.get_world();
-        let current_room = world.get_current_room_mut();
-        if let Some(new_item) = current_room.move_item(self.item.as_str()) {
-            let player = world.get_player_mut();
-            player.fill_backpack(new_item);
+        let too_heavy = !is_carried
+            && world
+                .container(&container_name)
+                .and_then(|found| {
+                    found
+                        .contents()
+                        .iter()
+                        .find(|item| item.name() == item_name)
+                })
+                .is_some_and(|item| !world.get_player().can_carry(&console, item, count));
+        if too_heavy {
+            continue;
+        }
+        match world
+            .container_mut(&container_name)
+            .and_then(|found| found.remove(&item_name, count))
+        {
+            Some(item) => {
+                console_message!(console, ItemTaken, item.label());
+                world.fill_player_backpack(item);
+            }
+            None => {
+                console_output!(
+                    console,
+                    "{}",
+                    console.styled(
+                        Style::Warning,
+                        message!(console, ItemNotInContainer, item_name, container_name)
+                    )
+                );
+            }
         }
     }
 }
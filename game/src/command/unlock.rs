@@ -0,0 +1,109 @@
+use std::fmt::Display;
+
+use crate::{
+    console::console::Style, console_message, console_output, message, presentation::base_name,
+    Game,
+};
+
+use super::{split_at_word, suggestions, Argument, Command, CommandSpec, Turn};
+
+pub(crate) struct UnlockCommand {
+    /// An exit, or a container when no exit has this name.
+    pub(crate) target: String,
+    /// The item to unlock with, any carried item that fits when `None`.
+    pub(crate) key: Option<String>,
+}
+
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Unlock",
+    verbs: &["unlock", "ontgrendel"],
+    argument: Argument::Required("Exit"),
+    help: "Unlock an exit or container with a key you carry, e.g. 'north with dolk'",
+    turn: Turn::EnemiesAttack,
+    examples: &["Unlock north", "Unlock north with dolk", "Unlock kist"],
+    suggestions: Some(suggestions::locked_exits),
+    handler: |game, args| {
+        let (target, key) = split_at_word(args, &["with", "met"]);
+        UnlockCommand { target, key }.execute(game)
+    },
+};
+
+impl Command for UnlockCommand {
+    fn execute(&self, game: &mut Game) {
+        let console = game.console().clone();
+        let language = console.language();
+        if let Some(exit) = game.choose_exit(&self.target) {
+            match exit.lock().map(str::to_string) {
+                Some(lock) => {
+                    let exit_name = message!(console, TheExit, exit.label(language));
+                    if let Some(key) = choose_key(game, &lock, self.key.as_deref(), &exit_name) {
+                        game.get_world().unlock_exit(&exit, &lock);
+                        console_message!(console, Unlocked, exit_name, key);
+                    }
+                }
+                None => {
+                    console_message!(console, ExitNotLocked, exit.label(language));
+                }
+            }
+        } else if let Some(container) = game.choose_container(&self.target) {
+            if !unlock_container(game, &container, self.key.as_deref()) {
+                console_message!(console, ContainerNotLocked, container);
+            }
+        } else {
+            console_output!(
+                console,
+                "{}",
+                console.styled(Style::Warning, message!(console, WrongDirection))
+            );
+        }
+    }
+}
+
+/// Unlocks the container named `container` when it is locked, returns whether it was.
+pub(super) fn unlock_container(game: &mut Game, container: &str, key: Option<&str>) -> bool {
+    let console = game.console().clone();
+    let Some(lock) = game
+        .world()
+        .container(container)
+        .and_then(|found| found.lock().map(str::to_string))
+    else {
+        return false;
+    };
+    if let Some(key) = choose_key(game, &lock, key, container) {
+        if let Some(found) = game.get_world().container_mut(container) {
+            found.unlock();
+        }
+        console_message!(console, Unlocked, container, key);
+    }
+    true
+}
+
+/// The carried item that fits `lock`: the one the player named with `key`, or the first
+/// that fits when they named none. Tells the player when they have no such item.
+fn choose_key(
+    game: &mut Game,
+    lock: &str,
+    key: Option<&str>,
+    locked: impl Display,
+) -> Option<String> {
+    let console = game.console().clone();
+    let carried = game.world().get_player().carried_item_names();
+    let key = match key {
+        // `choose_name` hands back unknown names as typed, only carried items count.
+        Some(key) => game
+            .choose_name(key, &carried)
+            .filter(|key| carried.contains(key)),
+        None => carried.into_iter().find(|name| base_name(name) == lock),
+    };
+    match key {
+        Some(key) if base_name(&key) == lock => Some(key),
+        Some(key) => {
+            console_message!(console, WrongKey, key, locked);
+            None
+        }
+        None => {
+            console_message!(console, NoKey, locked);
+            None
+        }
+    }
+}
@@ -1,13 +1,21 @@
 use crate::Game;
 
-use super::Command;
+use super::{Argument, Command, CommandSpec, Turn};
 
 pub struct WaitCommand {}
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Wait",
+    verbs: &["wait", "wacht"],
+    argument: Argument::None,
+    help: "Let the enemies take a turn",
+    turn: Turn::EnemiesAttackAndMove,
+    examples: &["Wait"],
+    suggestions: None,
+    handler: |game, _args| WaitCommand {}.execute(game),
+};
+
+/// Does nothing itself, `Game::execute` gives the enemies their turn after every command.
 impl Command for WaitCommand {
-    fn execute(&self, game: &mut Game) {
-        let world = game.get_world();
-        world.enemies_attack();
-        world.enemies_move();
-    }
+    fn execute(&self, _game: &mut Game) {}
 }
@@ -1,15 +1,36 @@
 use crate::Game;
 
-use super::Command;
+use super::{suggestions, Argument, Command, CommandSpec, Turn};
 
 pub(crate) struct WearCommand {
     pub(crate) item: String,
 }
 
+pub(crate) const COMMAND: CommandSpec = CommandSpec {
+    name: "Wear",
+    verbs: &["wear", "equip", "wield", "draag"],
+    argument: Argument::Required("Object"),
+    help: "Equip a weapon or armor from your backpack",
+    turn: Turn::Free,
+    examples: &["Wear langzwaard", "Wear maliënkolder"],
+    suggestions: Some(suggestions::equipment_names),
+    handler: |game, args| {
+        WearCommand {
+            item: args.to_string(),
+        }
+        .execute(game)
+    },
+};
+
 impl Command for WearCommand {
     fn execute(&self, game: &mut Game) {
+        let candidates = game.get_world().get_player().backpack_item_names();
+        let Some(item_name) = game.choose_name(&self.item, &candidates) else {
+            return;
+        };
+        let console = game.console().clone();
         let world = game.get_world();
         let player = world.get_player_mut();
-        player.equip_item(self.item.as_str());
+        player.equip_item(&console, item_name.as_str());
     }
 }
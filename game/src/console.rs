@@ -1,23 +1,433 @@
+#[allow(clippy::module_inception)]
 pub mod console {
-    use std::io::{self, Write};
+    use std::{
+        cell::RefCell,
+        collections::VecDeque,
+        fmt,
+        fs::{self, File},
+        io::{self, BufRead, Write},
+        path::{Path, PathBuf},
+        rc::Rc,
+    };
 
-    pub(crate) fn output(args: std::fmt::Arguments) {
-        print!("{}", args);
-        io::stdout().flush().unwrap();
+    use rustyline::{
+        completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+        history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+    };
+
+    use crate::messages::{Language, Localized};
+
+    /// Source of player input, one command per line.
+    pub trait InputSource {
+        /// Returns the next line without its line ending, or `None` when the input is exhausted.
+        fn read_line(&mut self) -> Option<String>;
+
+        /// Shows `prompt` on `output` and reads the next line. Sources that draw their own
+        /// prompt, like `LineEditor`, override this.
+        fn read_line_after_prompt(
+            &mut self,
+            prompt: &str,
+            output: &mut dyn OutputSink,
+        ) -> Option<String> {
+            output.write_str(prompt);
+            self.read_line()
+        }
+
+        /// Words worth completing on the next line, e.g. verbs and the names in the room.
+        fn set_completions(&mut self, _words: Vec<String>) {}
+
+        /// File to keep the lines the player typed in, across games.
+        fn set_history_file(&mut self, _path: &Path) {}
+    }
+
+    /// Sink that receives everything the game prints.
+    pub trait OutputSink {
+        fn write_str(&mut self, text: &str);
+    }
+
+    /// Reads from stdin and writes to stdout.
+    pub struct StdConsole;
+
+    impl InputSource for StdConsole {
+        fn read_line(&mut self) -> Option<String> {
+            let mut input = String::new();
+            match io::stdin().lock().read_line(&mut input) {
+                Ok(0) | Err(_) => None,
+                Ok(_) => Some(input.trim_end_matches(['\r', '\n']).to_string()),
+            }
+        }
+    }
+
+    impl OutputSink for StdConsole {
+        fn write_str(&mut self, text: &str) {
+            print!("{}", text);
+            io::stdout().flush().unwrap();
+        }
+    }
+
+    /// Reads from an interactive terminal with line editing, history and tab completion.
+    pub struct LineEditor {
+        editor: Editor<WordCompleter, DefaultHistory>,
+        history_file: Option<PathBuf>,
+    }
+
+    impl LineEditor {
+        pub fn new() -> io::Result<Self> {
+            let mut editor = Editor::new().map_err(io::Error::other)?;
+            editor.set_helper(Some(WordCompleter::default()));
+            Ok(Self {
+                editor,
+                history_file: None,
+            })
+        }
+    }
+
+    impl InputSource for LineEditor {
+        fn read_line(&mut self) -> Option<String> {
+            self.read_line_after_prompt("", &mut StdConsole)
+        }
+
+        fn read_line_after_prompt(
+            &mut self,
+            prompt: &str,
+            output: &mut dyn OutputSink,
+        ) -> Option<String> {
+            // The editor can only redraw the last line of the prompt, print the rest first.
+            let prompt = match prompt.rsplit_once('\n') {
+                Some((before, last_line)) => {
+                    output.write_str(&format!("{}\n", before));
+                    last_line
+                }
+                None => prompt,
+            };
+            match self.editor.readline(prompt) {
+                Ok(line) => {
+                    if !line.trim().is_empty() {
+                        let _ = self.editor.add_history_entry(line.as_str());
+                        if let Some(path) = &self.history_file {
+                            let _ = self.editor.append_history(path);
+                        }
+                    }
+                    Some(line)
+                }
+                // Ctrl-C and Ctrl-D end the game like the end of a script does.
+                Err(ReadlineError::Interrupted | ReadlineError::Eof) => None,
+                Err(_) => None,
+            }
+        }
+
+        fn set_completions(&mut self, words: Vec<String>) {
+            if let Some(helper) = self.editor.helper_mut() {
+                helper.words = words;
+            }
+        }
+
+        fn set_history_file(&mut self, path: &Path) {
+            if let Some(directory) = path.parent() {
+                let _ = fs::create_dir_all(directory);
+            }
+            let _ = self.editor.load_history(path);
+            self.history_file = Some(path.to_path_buf());
+        }
+    }
+
+    /// Completes the word, or words, before the cursor to one of `words`, ignoring case.
+    #[derive(Default)]
+    struct WordCompleter {
+        words: Vec<String>,
+    }
+
+    impl WordCompleter {
+        fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+            // Names can span several words, so try the longest text before the cursor first.
+            let starts = std::iter::once(0).chain(
+                line.char_indices()
+                    .filter(|(_, c)| c.is_whitespace() || *c == ';')
+                    .map(|(idx, c)| idx + c.len_utf8()),
+            );
+            for start in starts {
+                let typed = line[start..].to_lowercase();
+                let mut matches: Vec<String> = self
+                    .words
+                    .iter()
+                    .filter(|word| word.to_lowercase().starts_with(&typed))
+                    .cloned()
+                    .collect();
+                if !matches.is_empty() {
+                    matches.sort();
+                    matches.dedup();
+                    return (start, matches);
+                }
+            }
+            (line.len(), Vec::new())
+        }
+    }
+
+    impl Completer for WordCompleter {
+        type Candidate = String;
+
+        fn complete(
+            &self,
+            line: &str,
+            pos: usize,
+            _ctx: &Context<'_>,
+        ) -> rustyline::Result<(usize, Vec<String>)> {
+            Ok(self.candidates(&line[..pos]))
+        }
+    }
+
+    impl Hinter for WordCompleter {
+        type Hint = String;
+    }
+
+    impl Highlighter for WordCompleter {}
+
+    impl Validator for WordCompleter {}
+
+    impl Helper for WordCompleter {}
+
+    /// Feeds a fixed list of lines to the game.
+    pub struct MemoryInput {
+        lines: VecDeque<String>,
+    }
+
+    impl MemoryInput {
+        pub fn new<I, S>(lines: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            Self {
+                lines: lines.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl InputSource for MemoryInput {
+        fn read_line(&mut self) -> Option<String> {
+            self.lines.pop_front()
+        }
+    }
+
+    /// Replays commands from a script file, one per line, and echoes each one after the prompt
+    /// so a transcript reads like an interactive session.
+    pub struct ScriptInput {
+        lines: VecDeque<String>,
+    }
+
+    impl ScriptInput {
+        pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let script = fs::read_to_string(path)?;
+            Ok(Self {
+                lines: script.lines().map(str::to_string).collect(),
+            })
+        }
+    }
+
+    impl InputSource for ScriptInput {
+        fn read_line(&mut self) -> Option<String> {
+            self.lines.pop_front()
+        }
+
+        fn read_line_after_prompt(
+            &mut self,
+            prompt: &str,
+            output: &mut dyn OutputSink,
+        ) -> Option<String> {
+            output.write_str(prompt);
+            let line = self.read_line()?;
+            output.write_str(&format!("{}\n", line));
+            Some(line)
+        }
+    }
+
+    /// Collects output in a buffer. Clones share the same buffer, so keep one to inspect it.
+    #[derive(Clone, Default)]
+    pub struct MemoryOutput {
+        buffer: Rc<RefCell<String>>,
+    }
+
+    impl MemoryOutput {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn contents(&self) -> String {
+            self.buffer.borrow().clone()
+        }
+    }
+
+    impl OutputSink for MemoryOutput {
+        fn write_str(&mut self, text: &str) {
+            self.buffer.borrow_mut().push_str(text);
+        }
+    }
+
+    /// Writes all output to a file, e.g. to record a transcript.
+    pub struct FileOutput {
+        file: File,
+    }
+
+    impl FileOutput {
+        pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            Ok(Self {
+                file: File::create(path)?,
+            })
+        }
+    }
+
+    impl OutputSink for FileOutput {
+        fn write_str(&mut self, text: &str) {
+            self.file.write_all(text.as_bytes()).unwrap();
+        }
+    }
+
+    pub(crate) type SharedOutput = Rc<RefCell<Box<dyn OutputSink>>>;
+
+    /// Where a game prints to and how: its output, whether it uses colors and the language of
+    /// its messages. Clones print to the same output.
+    #[derive(Clone)]
+    pub(crate) struct Console {
+        output: SharedOutput,
+        color: bool,
+        language: Language,
+    }
+
+    impl Console {
+        pub(crate) fn new(output: SharedOutput, color: bool, language: Language) -> Self {
+            Self {
+                output,
+                color,
+                language,
+            }
+        }
+
+        /// A console for output outside a game, e.g. the leaderboard printed by `--leaderboard`.
+        pub(crate) fn stdout(color: bool, language: Language) -> Self {
+            Self::new(Rc::new(RefCell::new(Box::new(StdConsole))), color, language)
+        }
+
+        pub(crate) fn language(&self) -> Language {
+            self.language
+        }
+
+        pub(crate) fn write(&self, args: fmt::Arguments) {
+            self.output.borrow_mut().write_str(&args.to_string());
+        }
+
+        /// Wraps `value` so it is printed in ANSI colors when this console uses them, and as
+        /// plain text otherwise.
+        pub(crate) fn styled<T: fmt::Display>(&self, style: Style, value: T) -> Styled<T> {
+            Styled {
+                style,
+                color: self.color,
+                value,
+            }
+        }
+
+        /// Shows `PROMPT` and reads the player's next command from `input`.
+        pub(crate) fn read_input(&self, input: &mut dyn InputSource) -> Option<String> {
+            const PROMPT: &str = "\n> ";
+            input
+                .read_line_after_prompt(PROMPT, self.output.borrow_mut().as_mut())
+                .map(|line| line.trim().to_string())
+        }
     }
 
+    /// Prints to stdout, without colors and in English.
+    impl Default for Console {
+        fn default() -> Self {
+            Self::stdout(false, Language::default())
+        }
+    }
+
+    impl Localized for Console {
+        fn language(&self) -> Language {
+            self.language
+        }
+    }
+
+    /// What a piece of output is, so it can be told apart from the text around it.
+    #[derive(Clone, Copy, Debug)]
+    pub(crate) enum Style {
+        RoomTitle,
+        Enemy,
+        Damage,
+        Healing,
+        Gold,
+        Warning,
+    }
+
+    impl Style {
+        fn ansi_code(self) -> &'static str {
+            match self {
+                Style::RoomTitle => "1;36",
+                Style::Enemy => "1;31",
+                Style::Damage => "31",
+                Style::Healing => "32",
+                Style::Gold => "33",
+                Style::Warning => "35",
+            }
+        }
+    }
+
+    /// A value printed in a `Style`, see `Console::styled`.
+    pub(crate) struct Styled<T> {
+        style: Style,
+        color: bool,
+        value: T,
+    }
+
+    impl<T: fmt::Display> fmt::Display for Styled<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            if self.color {
+                write!(f, "\x1b[{}m{}\x1b[0m", self.style.ansi_code(), self.value)
+            } else {
+                write!(f, "{}", self.value)
+            }
+        }
+    }
+
+    /// Prints to a `Console`, e.g. `console_output!(console, "{}\n", room)`.
     #[macro_export]
     macro_rules! console_output {
-        ($($arg:tt)*) => {
-            $crate::console::console::output(format_args!($($arg)*));
+        ($console:expr, $($arg:tt)*) => {
+            $console.write(format_args!($($arg)*));
         };
     }
 
-    pub fn read_input() -> String {
-        const PROMPT: &str = "\n> ";
-        output(format_args!("{}", PROMPT));
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        input.trim().to_string()
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn completer(words: &[&str]) -> WordCompleter {
+            WordCompleter {
+                words: words.iter().map(|word| word.to_string()).collect(),
+            }
+        }
+
+        #[test]
+        fn test_completes_the_last_word() {
+            let completer = completer(&["take", "Goblin", "north", "noord"]);
+            assert_eq!(completer.candidates("ta"), (0, vec!["take".to_string()]));
+            assert_eq!(
+                completer.candidates("Hit gob"),
+                (4, vec!["Goblin".to_string()])
+            );
+            assert_eq!(
+                completer.candidates("go no"),
+                (3, vec!["noord".to_string(), "north".to_string()])
+            );
+        }
+
+        #[test]
+        fn test_completes_names_with_spaces() {
+            let completer = completer(&["klein levenselixer", "kleine buidel"]);
+            assert_eq!(
+                completer.candidates("Take klein l"),
+                (5, vec!["klein levenselixer".to_string()])
+            );
+            assert_eq!(completer.candidates("Take zwaard"), (11, Vec::new()));
+        }
     }
 }
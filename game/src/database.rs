@@ -1,11 +1,19 @@
-use sqlite::{Connection, State};
+use std::{io, path::Path};
 
+use sqlite::{Connection, State, Statement};
+
+use crate::error::GameError;
+
+/// Weight of objects when neither they nor their type have a `gewicht`.
+const DEFAULT_WEIGHT: i64 = 1;
 
 pub(crate) struct Object {
     pub(crate) name: String,
     pub(crate) description: String,
     pub(crate) type_obj: String,
     pub(crate) extra_parameters: (i64, i64, i64),
+    /// From `Objecten.gewicht`, or `Objecttypen.gewicht` of its type when that is empty.
+    pub(crate) weight: i64,
 }
 
 #[allow(dead_code)]
@@ -14,9 +22,9 @@ struct Location {
     description: String,
 }
 
-#[allow(dead_code)]
 struct ObjectType {
     name: String,
+    weight: Option<i64>,
 }
 
 pub(crate) struct Enemy {
@@ -30,6 +38,11 @@ pub(crate) struct Enemy {
     pub(crate) maximum_damage: i64,
 }
 
+pub(crate) struct LeaderboardEntry {
+    pub(crate) name: String,
+    pub(crate) gold: i64,
+}
+
 struct SqlData {
     objects: Vec<Object>,
     locations: Vec<Location>,
@@ -38,14 +51,42 @@ struct SqlData {
 }
 
 pub(crate) struct Database {
-    connection: Option<Connection>,
+    connection: Connection,
     data: SqlData,
 }
 
+/// Reads a column that databases from before it was added do not have.
+fn read_optional(statement: &Statement, column: &str) -> sqlite::Result<Option<i64>> {
+    if statement.column_names().iter().any(|name| name == column) {
+        statement.read::<Option<i64>, _>(column)
+    } else {
+        Ok(None)
+    }
+}
+
+fn schema_error(table: &'static str, error: sqlite::Error) -> GameError {
+    GameError::DatabaseSchema {
+        table,
+        message: error.to_string(),
+    }
+}
+
 impl Database {
-    pub(crate) fn new(path: &str) -> Self {
+    pub(crate) fn new(path: &Path) -> Result<Self, GameError> {
+        // sqlite would silently create an empty database for a wrong path.
+        if !path.is_file() {
+            return Err(GameError::MissingFile {
+                path: path.to_path_buf(),
+                source: io::Error::from(io::ErrorKind::NotFound),
+            });
+        }
+        let connection = sqlite::open(path).map_err(|error| GameError::Database {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        })?;
+
         let mut db = Self {
-            connection: None,
+            connection,
             data: SqlData {
                 objects: Vec::new(),
                 enemies: Vec::new(),
@@ -53,112 +94,177 @@ impl Database {
                 object_types: Vec::new(),
             },
         };
+        db.load_data()?;
 
-        db.open_connection(path);
-        db.load_data();
-
-        db
+        Ok(db)
     }
 
-    fn open_connection(&mut self, path: &str) {
-        match sqlite::open(path) {
-            Ok(conn) => {
-                self.connection = Some(conn);
-            }
-            Err(error) => eprintln!("Failed to open databse connection {}", error),
-        };
+    fn load_data(&mut self) -> Result<(), GameError> {
+        self.load_object_types()
+            .map_err(|error| schema_error("Objecttypen", error))?;
+        self.load_objects()
+            .map_err(|error| schema_error("Objecten", error))?;
+        self.load_locations()
+            .map_err(|error| schema_error("Locaties", error))?;
+        self.load_enemies()
+            .map_err(|error| schema_error("Vijanden", error))?;
+        Ok(())
     }
-    fn load_data(&mut self) {
-        self.load_object_types();
-        self.load_objects();
-        self.load_locations();
-        self.load_enemies();
-    }
-    fn load_object_types(&mut self) {
-        if let Some(connection) = &self.connection {
-            let query = "SELECT * FROM Objecttypen";
-            let mut statement = connection.prepare(query).unwrap();
-
-            while let Ok(State::Row) = statement.next() {
-                self.data.object_types.push(ObjectType {
-                    name: statement.read::<String, _>("naam").unwrap(),
-                })
-            }
+
+    fn load_object_types(&mut self) -> sqlite::Result<()> {
+        let query = "SELECT * FROM Objecttypen";
+        let mut statement = self.connection.prepare(query)?;
+
+        while let State::Row = statement.next()? {
+            self.data.object_types.push(ObjectType {
+                name: statement.read::<String, _>("naam")?,
+                weight: read_optional(&statement, "gewicht")?,
+            })
         }
+        Ok(())
     }
 
-    fn load_locations(&mut self) {
-        if let Some(connection) = &self.connection {
-            let query = "SELECT * FROM Locaties";
-            let mut statement = connection.prepare(query).unwrap();
+    fn load_locations(&mut self) -> sqlite::Result<()> {
+        let query = "SELECT * FROM Locaties";
+        let mut statement = self.connection.prepare(query)?;
 
-            while let Ok(State::Row) = statement.next() {
-                self.data.locations.push(Location {
-                    name: statement.read::<String, _>("naam").unwrap(),
-                    description: statement.read::<String, _>("beschrijving").unwrap(),
-                })
-            }
+        while let State::Row = statement.next()? {
+            self.data.locations.push(Location {
+                name: statement.read::<String, _>("naam")?,
+                description: statement.read::<String, _>("beschrijving")?,
+            })
         }
+        Ok(())
     }
 
-    fn load_objects(&mut self) {
-        if let Some(connection) = &self.connection {
-            let query = "SELECT * FROM Objecten";
-            let mut statement = connection.prepare(query).unwrap();
-
-            while let Ok(State::Row) = statement.next() {
-                self.data.objects.push(Object {
-                    name: statement.read::<String, _>("naam").unwrap(),
-                    description: statement.read::<String, _>("omschrijving").unwrap(),
-                    type_obj: statement.read::<String, _>("type").unwrap(),
-                    extra_parameters: (
-                        statement.read::<i64, _>("minimumwaarde").unwrap(),
-                        statement.read::<i64, _>("maximumwaarde").unwrap(),
-                        statement.read::<i64, _>("bescherming").unwrap(),
-                    ),
-                })
-            }
+    fn load_objects(&mut self) -> sqlite::Result<()> {
+        let query = "SELECT * FROM Objecten";
+        let mut statement = self.connection.prepare(query)?;
+
+        while let State::Row = statement.next()? {
+            let type_obj = statement.read::<String, _>("type")?;
+            let type_weight = self
+                .data
+                .object_types
+                .iter()
+                .find(|object_type| object_type.name == type_obj)
+                .and_then(|object_type| object_type.weight);
+            self.data.objects.push(Object {
+                name: statement.read::<String, _>("naam")?,
+                description: statement.read::<String, _>("omschrijving")?,
+                extra_parameters: (
+                    statement.read::<i64, _>("minimumwaarde")?,
+                    statement.read::<i64, _>("maximumwaarde")?,
+                    statement.read::<i64, _>("bescherming")?,
+                ),
+                weight: read_optional(&statement, "gewicht")?
+                    .or(type_weight)
+                    .unwrap_or(DEFAULT_WEIGHT),
+                type_obj,
+            })
         }
+        Ok(())
     }
 
-    fn load_enemies(&mut self) {
-        if let Some(connection) = &self.connection {
-            let query = "SELECT * FROM Vijanden";
-            let mut statement = connection.prepare(query).unwrap();
-
-            while let Ok(State::Row) = statement.next() {
-                self.data.enemies.push(Enemy {
-                    name: statement.read::<String, _>("naam").unwrap(),
-                    description: statement.read::<String, _>("omschrijving").unwrap(),
-                    minimum_objects: statement.read::<i64, _>("minimumobjecten").unwrap(),
-                    maximum_objects: statement.read::<i64, _>("maximumobjecten").unwrap(),
-                    hp: statement.read::<i64, _>("levenspunten").unwrap(),
-                    attack_chance: statement.read::<i64, _>("aanvalskans").unwrap(),
-                    minimum_damage: statement.read::<i64, _>("minimumschade").unwrap(),
-                    maximum_damage: statement.read::<i64, _>("maximumschade").unwrap(),
-                })
-            }
+    fn load_enemies(&mut self) -> sqlite::Result<()> {
+        let query = "SELECT * FROM Vijanden";
+        let mut statement = self.connection.prepare(query)?;
+
+        while let State::Row = statement.next()? {
+            self.data.enemies.push(Enemy {
+                name: statement.read::<String, _>("naam")?,
+                description: statement.read::<String, _>("omschrijving")?,
+                minimum_objects: statement.read::<i64, _>("minimumobjecten")?,
+                maximum_objects: statement.read::<i64, _>("maximumobjecten")?,
+                hp: statement.read::<i64, _>("levenspunten")?,
+                attack_chance: statement.read::<i64, _>("aanvalskans")?,
+                minimum_damage: statement.read::<i64, _>("minimumschade")?,
+                maximum_damage: statement.read::<i64, _>("maximumschade")?,
+            })
         }
+        Ok(())
     }
 
     pub(crate) fn get_object(&self, name: &str) -> Option<&Object> {
-        for object in &self.data.objects {
-            if object.name == name {
-                return Some(object);
-            }
-        }
-        None
+        self.data.objects.iter().find(|object| object.name == name)
     }
 
     pub(crate) fn get_enemy(&self, name: &str) -> Option<&Enemy> {
-        for enemy in &self.data.enemies {
-            if enemy.name == name {
-                return Some(enemy);
-            }
-        }
-        None
+        self.data.enemies.iter().find(|enemy| enemy.name == name)
     }
     pub(crate) fn get_all_objects(&self) -> &Vec<Object> {
         &self.data.objects
     }
+
+    pub(crate) fn add_leaderboard_entry(&self, name: &str, gold: u32) -> sqlite::Result<()> {
+        let query = "INSERT INTO Leaderboard (naam, goudstukken) VALUES (?, ?)";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((1, name))?;
+        statement.bind((2, gold as i64))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Returns the `count` entries with the most gold, earlier entries first on a tie.
+    pub(crate) fn get_leaderboard(&self, count: usize) -> sqlite::Result<Vec<LeaderboardEntry>> {
+        let query =
+            "SELECT naam, goudstukken FROM Leaderboard ORDER BY goudstukken DESC, ID ASC LIMIT ?";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((1, count as i64))?;
+
+        let mut entries = Vec::new();
+        while let State::Row = statement.next()? {
+            entries.push(LeaderboardEntry {
+                name: statement.read::<String, _>("naam")?,
+                gold: statement.read::<i64, _>("goudstukken")?,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_story::{TempDir, DATABASE};
+
+    #[test]
+    fn test_object_weight_defaults_to_its_type() {
+        let db = Database::new(Path::new(DATABASE)).unwrap();
+        let weight = |name: &str| db.get_object(name).unwrap().weight;
+        assert_eq!(weight("harnas"), 15);
+        assert_eq!(weight("speer"), 4);
+        assert_eq!(weight("klein levenselixer"), 1);
+    }
+
+    #[test]
+    fn test_database_without_weights() {
+        let directory = TempDir::new("weightless");
+        let path = directory.database();
+        let connection = sqlite::open(&path).unwrap();
+        connection
+            .execute(
+                "ALTER TABLE Objecten DROP COLUMN gewicht; ALTER TABLE Objecttypen DROP COLUMN gewicht",
+            )
+            .unwrap();
+        drop(connection);
+        let db = Database::new(&path).unwrap();
+        assert_eq!(db.get_object("harnas").unwrap().weight, DEFAULT_WEIGHT);
+    }
+
+    #[test]
+    fn test_leaderboard_is_sorted_by_gold() {
+        let directory = TempDir::new("leaderboard");
+        let path = directory.database();
+        let db = Database::new(&path).unwrap();
+
+        db.add_leaderboard_entry("Poor", 3).unwrap();
+        db.add_leaderboard_entry("Rich", 300).unwrap();
+        db.add_leaderboard_entry("Middle", 30).unwrap();
+        let top = db.get_leaderboard(2).unwrap();
+
+        let names: Vec<&str> = top.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["Rich", "Middle"]);
+        assert_eq!(top[0].gold, 300);
+    }
 }
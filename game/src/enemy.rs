@@ -1,10 +1,18 @@
-use std::{fmt, mem};
+use std::mem;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::{console_output, objects::Item, room::RoomId};
+use crate::{
+    console::console::{Console, Style},
+    console_message, console_output, message,
+    objects::Item,
+    presentation::item_list,
+    room::RoomId,
+    world::GameRng,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct Enemy {
     id: RoomId,
     name: String,
@@ -16,39 +24,8 @@ pub(crate) struct Enemy {
     invisible_items: Vec<Item>,
 }
 
-impl fmt::Display for Enemy {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "\n Enemy Details:
---------------------
-Name:           {}
-Description:    {}
-HP:             {}
-Damage Range:   {} - {}
-Room ID:        {}
-Invisible Items: {}
---------------------\n",
-            self.name,
-            self.description,
-            self.hp,
-            self.minimum_damage,
-            self.maximum_damage,
-            self.id,
-            if self.invisible_items.is_empty() {
-                "None".to_string()
-            } else {
-                self.invisible_items
-                    .iter()
-                    .map(|item| format!("{:?}", item))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            }
-        )
-    }
-}
-
 impl Enemy {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         id: RoomId,
         name: &str,
@@ -71,26 +48,38 @@ impl Enemy {
         }
     }
 
-    pub(crate) fn attack(&self) -> u32 {
+    pub(crate) fn attack(&self, console: &Console, rng: &mut GameRng) -> u32 {
         if self.hp == 0 {
             return 0;
         }
 
-        let mut rng = thread_rng();
         let attack_procentage = self.attack_chance as f64 / 100.0;
         if rng.gen_bool(attack_procentage) {
             let damage = rng.gen_range(self.minimum_damage..=self.maximum_damage);
-            console_output!("{} hits the player for {}\n", self.name, damage);
+            console_message!(
+                console,
+                EnemyHits,
+                console.styled(Style::Enemy, &self.name),
+                console.styled(Style::Damage, damage)
+            );
             damage
         } else {
-            console_output!("{} misses the player\n", self.name);
+            console_message!(
+                console,
+                EnemyMisses,
+                console.styled(Style::Enemy, &self.name)
+            );
             0
         }
     }
 
-    pub(crate) fn take_dmg(&mut self, dmg: u32) {
+    pub(crate) fn take_dmg(&mut self, console: &Console, dmg: u32) {
         if self.hp == 0 {
-            console_output!("Enemy: {} is already dead\n", self.name);
+            console_message!(
+                console,
+                EnemyAlreadyDead,
+                console.styled(Style::Enemy, &self.name)
+            );
             return;
         } else if dmg == 0 {
             return;
@@ -98,20 +87,41 @@ impl Enemy {
 
         if self.hp > dmg {
             self.hp -= dmg;
-            console_output!(
-                "{} took {} damage, {} HP remaining.\n",
-                self.name,
-                dmg,
+            console_message!(
+                console,
+                EnemyWounded,
+                console.styled(Style::Enemy, &self.name),
+                console.styled(Style::Damage, dmg),
                 self.hp
             );
         } else {
             self.hp = 0;
-            console_output!("{} took {} damage and has died.\n", self.name, dmg);
+            console_message!(
+                console,
+                EnemyDied,
+                console.styled(Style::Enemy, &self.name),
+                console.styled(Style::Damage, dmg)
+            );
         }
     }
 
-    pub(crate) fn show(&self) {
-        console_output!("{self}\n");
+    pub(crate) fn show(&self, console: &Console) {
+        console_message!(
+            console,
+            EnemyDetails,
+            console.styled(Style::Enemy, &self.name),
+            self.description,
+            self.hp,
+            self.minimum_damage,
+            self.maximum_damage,
+            self.id,
+            if self.invisible_items.is_empty() {
+                message!(console, NoEquipment)
+            } else {
+                item_list(console.language(), &self.invisible_items)
+            }
+        );
+        console_output!(console, "\n");
     }
 
     pub(crate) fn set_position(&mut self, new_room: RoomId) {
@@ -0,0 +1,168 @@
+use std::{fmt, io, path::PathBuf};
+
+use crate::room::RoomId;
+
+/// Everything that can go wrong while loading a story and its database, and the problems
+/// `--check` reports in a story that still loads.
+#[derive(Debug)]
+pub enum GameError {
+    MissingFile {
+        path: PathBuf,
+        source: io::Error,
+    },
+    MalformedXml {
+        path: PathBuf,
+        location: Option<RoomId>,
+        message: String,
+    },
+    EmptyStory {
+        path: PathBuf,
+    },
+    UnknownObject {
+        location: Option<RoomId>,
+        name: String,
+    },
+    UnknownEnemy {
+        location: RoomId,
+        name: String,
+    },
+    UnknownObjectType {
+        object: String,
+        object_type: String,
+    },
+    BadExitTarget {
+        location: RoomId,
+        direction: String,
+        target: RoomId,
+    },
+    DuplicateLocation {
+        location: RoomId,
+    },
+    OneWayExit {
+        location: RoomId,
+        direction: String,
+        target: RoomId,
+    },
+    UnreachableLocation {
+        location: RoomId,
+    },
+    UnknownFlag {
+        location: RoomId,
+        direction: String,
+        flag: String,
+    },
+    Database {
+        path: PathBuf,
+        message: String,
+    },
+    DatabaseSchema {
+        table: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::MissingFile { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            GameError::MalformedXml {
+                path,
+                location: Some(location),
+                message,
+            } => write!(
+                f,
+                "{}: malformed XML in location {}: {}",
+                path.display(),
+                location,
+                message
+            ),
+            GameError::MalformedXml {
+                path,
+                location: None,
+                message,
+            } => write!(f, "{}: malformed XML: {}", path.display(), message),
+            GameError::EmptyStory { path } => {
+                write!(f, "{} does not contain any locations", path.display())
+            }
+            GameError::UnknownObject {
+                location: Some(location),
+                name,
+            } => write!(
+                f,
+                "location {} refers to object '{}', which is not in the database",
+                location, name
+            ),
+            GameError::UnknownObject {
+                location: None,
+                name,
+            } => write!(f, "object '{}' is not in the database", name),
+            GameError::UnknownEnemy { location, name } => write!(
+                f,
+                "location {} refers to enemy '{}', which is not in the database",
+                location, name
+            ),
+            GameError::UnknownObjectType {
+                object,
+                object_type,
+            } => write!(
+                f,
+                "object '{}' in the database has unknown type '{}'",
+                object, object_type
+            ),
+            GameError::BadExitTarget {
+                location,
+                direction,
+                target,
+            } => write!(
+                f,
+                "location {} has exit {} to location {}, which does not exist",
+                location, direction, target
+            ),
+            GameError::DuplicateLocation { location } => {
+                write!(f, "location id {} is used more than once", location)
+            }
+            GameError::OneWayExit {
+                location,
+                direction,
+                target,
+            } => write!(
+                f,
+                "location {} has exit {} to location {}, which has no exit back",
+                location, direction, target
+            ),
+            GameError::UnreachableLocation { location } => write!(
+                f,
+                "location {} cannot be reached from the first location",
+                location
+            ),
+            GameError::UnknownFlag {
+                location,
+                direction,
+                flag,
+            } => write!(
+                f,
+                "location {} has exit {} waiting for flag '{}', which no location sets",
+                location, direction, flag
+            ),
+            GameError::Database { path, message } => {
+                write!(f, "cannot open database {}: {}", path.display(), message)
+            }
+            GameError::DatabaseSchema { table, message } => write!(
+                f,
+                "database table {} does not match the expected schema: {}",
+                table, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::MissingFile { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
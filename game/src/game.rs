@@ -1,51 +1,598 @@
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use rand::{thread_rng, Rng};
+
 use crate::{
-    command::{parse_input_to_command, Command}, console::console::read_input, console_output, room::Room, world::World
+    command::{split_input, Argument, CommandRegistry, CommandSpec, Turn},
+    console::console::{Console, InputSource, OutputSink, SharedOutput, StdConsole, Style},
+    console_message, console_output,
+    error::GameError,
+    leaderboard::{record_score, show_leaderboard, DEFAULT_LEADERBOARD_SIZE},
+    message,
+    messages::Language,
+    name_resolver::{resolve_name, ItemSelection, Resolution},
+    objects::ItemCategory,
+    paths::GamePaths,
+    room::{Exit, Room},
+    story::Story,
+    world::World,
 };
 
+/// Lines typed at the prompt are kept here, in the save directory.
+const HISTORY_FILE: &str = "history.txt";
+
+/// Seed used for scripted runs so their transcripts are reproducible.
+pub const SCRIPT_SEED: u64 = 0;
+
+#[derive(Clone, Debug, Default)]
+pub struct GameOptions {
+    /// Seed for every random decision in the game. A random seed is used when `None`,
+    /// it is printed at startup so the run can be replayed.
+    pub seed: Option<u64>,
+    /// Story XML. Taken from `KERKERS_STORY` or searched for in the `assets` directory when `None`.
+    pub story_path: Option<PathBuf>,
+    /// Object and enemy database. Taken from `KERKERS_DATABASE` or searched for next to the story
+    /// and in the `assets` directory when `None`.
+    pub database_path: Option<PathBuf>,
+    /// Directory for `Save` and `Load`. Taken from `KERKERS_SAVES` or `saves` next to the
+    /// story's directory when `None`.
+    pub save_directory: Option<PathBuf>,
+    /// Add the player's name and gold to the database leaderboard when the game ends.
+    pub record_score: bool,
+    /// Print room names, enemies, damage and such in ANSI colors.
+    pub color: bool,
+    /// Language of the messages, and of the room descriptions when the story has them in it.
+    pub language: Language,
+}
+
 pub struct Game {
     world: World,
     running: bool,
+    input: Box<dyn InputSource>,
+    /// Where the game prints to, the world prints to a clone of it.
+    console: Console,
+    paths: GamePaths,
+    record_score: bool,
+    commands: CommandRegistry,
 }
 
 impl Game {
-    pub fn new() -> Self {
-        Game {
-            world: World::new(),
+    pub fn new() -> Result<Self, GameError> {
+        Self::with_io(Box::new(StdConsole), Box::new(StdConsole))
+    }
+
+    /// Creates a game that reads commands from `input` and writes everything to `output`.
+    /// The first line of `input` is the player name.
+    pub fn with_io(
+        input: Box<dyn InputSource>,
+        output: Box<dyn OutputSink>,
+    ) -> Result<Self, GameError> {
+        Self::with_options(GameOptions::default(), input, output)
+    }
+
+    pub fn with_options(
+        options: GameOptions,
+        mut input: Box<dyn InputSource>,
+        output: Box<dyn OutputSink>,
+    ) -> Result<Self, GameError> {
+        let output: SharedOutput = Rc::new(RefCell::new(output));
+        let console = Console::new(output, options.color, options.language);
+        let seed = options.seed.unwrap_or_else(|| thread_rng().gen());
+        let paths = GamePaths::resolve(
+            options.story_path.as_deref(),
+            options.database_path.as_deref(),
+            options.save_directory.as_deref(),
+        );
+        input.set_history_file(&paths.save_directory.join(HISTORY_FILE));
+        // A broken story or database is reported before the player is asked for a name.
+        let story = Story::new(&paths.story, &paths.database)?;
+        let player_name = console.read_input(input.as_mut()).unwrap_or_default();
+
+        let world = World::new(player_name.as_str(), seed, &story, console.clone())?;
+
+        Ok(Game {
+            world,
             running: true,
+            input,
+            console,
+            paths,
+            record_score: options.record_score,
+            commands: CommandRegistry::new(),
+        })
+    }
+
+    /// Prints the best `DEFAULT_LEADERBOARD_SIZE` scores from the database `options` points at.
+    pub fn show_leaderboard(options: &GameOptions) {
+        let paths = GamePaths::resolve(
+            options.story_path.as_deref(),
+            options.database_path.as_deref(),
+            options.save_directory.as_deref(),
+        );
+        show_leaderboard(
+            &Console::stdout(options.color, options.language),
+            &paths.database,
+            DEFAULT_LEADERBOARD_SIZE,
+        );
+    }
+
+    /// Prints every problem in the story and database `options` points at, see `Story::check`.
+    /// Returns whether the story is free of problems.
+    pub fn check_story(options: &GameOptions) -> bool {
+        let console = Console::stdout(false, Language::English);
+        let paths = GamePaths::resolve(
+            options.story_path.as_deref(),
+            options.database_path.as_deref(),
+            options.save_directory.as_deref(),
+        );
+        let story = match Story::load(&paths.story, &paths.database) {
+            Ok(story) => story,
+            Err(error) => {
+                console_output!(console, "{}\n", error);
+                return false;
+            }
+        };
+
+        let problems = story.check();
+        for problem in &problems {
+            console_output!(console, "{}: {}\n", paths.story.display(), problem);
+        }
+        match problems.len() {
+            0 => {
+                console_output!(console, "No problems found in {}\n", paths.story.display());
+            }
+            1 => {
+                console_output!(console, "1 problem found\n");
+            }
+            count => {
+                console_output!(console, "{} problems found\n", count);
+            }
         }
+        problems.is_empty()
     }
 
     pub fn run(&mut self) {
-        console_output!("Welcome to kerkers en draken. A simple text adventure game made in rust for a school project learning rust \n");
-        console_output!("Everytime the prompt shows '>' insert a command. if player does not know any commands insert Help");
+        console_message!(self.console, Welcome);
+        console_message!(self.console, Instructions);
+        console_message!(self.console, Seed, self.world.seed());
         while self.running {
-            let input = self.get_player_input();
-            let command = parse_input_to_command(input.as_str());
-            command.execute(self);
+            // End of input or Ctrl-C ends the game like Quit, so the score still counts.
+            let Some(input) = self.get_player_input() else {
+                self.end_game();
+                break;
+            };
+            for command_input in split_input(input.as_str()) {
+                self.execute(command_input.as_str());
 
-            self.check_player_hp();
+                self.check_player_hp();
+                if !self.running {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Adds a command the player can use, next to the built-in ones. Its verbs take precedence,
+    /// so a command can also replace a built-in one.
+    pub fn register_command(&mut self, command: CommandSpec) {
+        self.commands.register(command);
+    }
+
+    /// Prints `text` wherever the game's output goes.
+    pub fn print(&self, text: &str) {
+        console_output!(self.console, "{}", text);
+    }
+
+    /// Sets a flag that exits in the story can wait for, like entering a room with a `vlag` does.
+    pub fn set_flag(&mut self, flag: &str) {
+        self.world.set_flag(flag);
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.world.has_flag(flag)
+    }
+
+    fn execute(&mut self, input: &str) {
+        let console = &self.console;
+        let Some((command, args)) = self.commands.parse(input) else {
+            console_output!(
+                console,
+                "{}",
+                console.styled(Style::Warning, message!(console, NotACommand))
+            );
+            return;
+        };
+        if matches!(command.argument, Argument::Required(_)) && args.is_empty() {
+            let usage = command.usage(console.language());
+            console_output!(
+                console,
+                "{}",
+                console.styled(Style::Warning, message!(console, Usage, usage))
+            );
+            return;
+        }
+        let (handler, turn) = (command.handler, command.turn);
+        handler(self, &args);
+        match turn {
+            Turn::Free => {}
+            Turn::EnemiesAttack => self.world.enemies_attack(),
+            Turn::EnemiesAttackAndMove => {
+                self.world.enemies_attack();
+                self.world.enemies_move();
+            }
         }
     }
 
     fn check_player_hp(&mut self) {
-        if self.world.get_player().hp() <= 0 {
-            self.running = false;
+        if self.world.get_player().hp() == 0 {
+            self.end_game();
         }
     }
 
     pub(crate) fn quit_game(&mut self) {
+        self.end_game();
+    }
+
+    fn end_game(&mut self) {
         self.running = false;
+        if self.record_score {
+            let player = self.world.get_player();
+            record_score(
+                &self.console,
+                &self.paths.database,
+                player.name(),
+                player.gold(),
+            );
+        }
+    }
+
+    pub(crate) fn command_registry(&self) -> &CommandRegistry {
+        &self.commands
+    }
+
+    pub(crate) fn database_path(&self) -> &Path {
+        &self.paths.database
     }
 
     pub(crate) fn get_current_room(&self) -> &Room {
         self.world.get_current_room()
     }
 
+    pub(crate) fn world(&self) -> &World {
+        &self.world
+    }
+
     pub(crate) fn get_world(&mut self) -> &mut World {
         &mut self.world
     }
 
-    fn get_player_input(&self) -> String {
-        read_input()
+    pub(crate) fn save_directory(&self) -> &Path {
+        &self.paths.save_directory
+    }
+
+    /// Plays on in `world`, e.g. one loaded from a save, printing to this game's console.
+    pub(crate) fn replace_world(&mut self, mut world: World) {
+        world.set_console(self.console.clone());
+        self.world = world;
+    }
+
+    pub(crate) fn console(&self) -> &Console {
+        &self.console
+    }
+
+    /// Turns the name the player typed into one of `candidates`, asking which one they mean
+    /// when several match. Returns the name as typed when nothing matches so the caller reports
+    /// it, and `None` when the player does not pick one.
+    pub(crate) fn choose_name(&mut self, query: &str, candidates: &[String]) -> Option<String> {
+        let matches = match resolve_name(query, candidates) {
+            Resolution::Found(name) => return Some(name),
+            Resolution::NotFound => return Some(query.to_string()),
+            Resolution::Ambiguous(matches) => matches,
+        };
+
+        console_message!(self.console, WhichOne);
+        for (number, name) in matches.iter().enumerate() {
+            console_output!(self.console, "  {}. {}\n", number + 1, name);
+        }
+        let answer = self.get_player_input()?;
+        match resolve_name(&answer, &matches) {
+            Resolution::Found(name) => Some(name),
+            _ => {
+                console_message!(self.console, NoneOfThem, answer);
+                None
+            }
+        }
+    }
+
+    /// The exit of the current room the player means with `input`: a direction, or the name of
+    /// an exit, which can be shortened like item names, e.g. "put" for "waterput".
+    pub(crate) fn choose_exit(&mut self, input: &str) -> Option<Exit> {
+        if let Some(exit) = self.get_current_room().check_direction(input) {
+            return Some(exit.clone());
+        }
+        let names = self.get_current_room().exit_names();
+        let name = self.choose_name(input, &names)?;
+        self.get_current_room().check_direction(&name).cloned()
+    }
+
+    /// The container in the room or the backpack the player means with `input`.
+    pub(crate) fn choose_container(&mut self, input: &str) -> Option<String> {
+        let names = self.world.container_names();
+        self.choose_name(input, &names)
+            .filter(|name| names.contains(name))
+    }
+
+    /// The names of the `candidates` the player selected with `input`, see `ItemSelection`.
+    /// Names in a list go through `choose_name` one at a time.
+    pub(crate) fn choose_items(
+        &mut self,
+        input: &str,
+        candidates: &[(String, ItemCategory)],
+    ) -> Vec<String> {
+        let names: Vec<String> = candidates.iter().map(|(name, _)| name.clone()).collect();
+        match ItemSelection::parse(input) {
+            ItemSelection::All { category, except } => {
+                let except: Vec<String> = except
+                    .iter()
+                    .filter_map(|name| self.choose_name(name, &names))
+                    .collect();
+                let selected: Vec<String> = candidates
+                    .iter()
+                    .filter(|(_, item_category)| category.is_none_or(|c| c == *item_category))
+                    .map(|(name, _)| name.clone())
+                    .filter(|name| !except.contains(name))
+                    .collect();
+                if selected.is_empty() {
+                    console_message!(self.console, NothingMatches, input);
+                }
+                selected
+            }
+            ItemSelection::Listed(list) => list
+                .iter()
+                .filter_map(|name| self.choose_name(name, &names))
+                .collect(),
+        }
+    }
+
+    /// Verbs and everything commands could be used on right now, for tab completion.
+    fn completions(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        for command in self.commands.commands() {
+            words.extend(command.verbs.iter().map(|verb| verb.to_string()));
+            if let Some(suggestions) = command.suggestions {
+                words.extend(suggestions(self));
+            }
+        }
+        words
+    }
+
+    fn get_player_input(&mut self) -> Option<String> {
+        let completions = self.completions();
+        self.input.set_completions(completions);
+        self.console.read_input(self.input.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        console::console::{MemoryInput, MemoryOutput},
+        database::Database,
+        test_story::TempDir,
+    };
+
+    #[test]
+    fn test_game_runs_headless() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Look", "Quit"]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        assert!(transcript.contains("Kasteelpoort"));
+        assert!(transcript.ends_with("End Game\n"));
+    }
+
+    #[test]
+    fn test_broken_story_fails_before_asking_for_a_name() {
+        let output = MemoryOutput::new();
+        let options = GameOptions {
+            story_path: Some(PathBuf::from("missing.xml")),
+            ..GameOptions::default()
+        };
+        let input = MemoryInput::new(["Tester"]);
+        let game = Game::with_options(options, Box::new(input), Box::new(output.clone()));
+
+        assert!(game.is_err());
+        assert!(!output.contents().contains('>'));
+    }
+
+    #[test]
+    fn test_game_stops_when_input_is_exhausted() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Wait"]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        assert!(output.contents().contains("Welcome to kerkers en draken"));
+    }
+
+    #[test]
+    fn test_score_is_recorded_when_input_ends() {
+        let directory = TempDir::new("score_at_end_of_input");
+        let database = directory.database();
+        let options = GameOptions {
+            database_path: Some(database.clone()),
+            record_score: true,
+            ..GameOptions::default()
+        };
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Look"]);
+        let mut game =
+            Game::with_options(options, Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        assert!(output
+            .contents()
+            .contains("Tester is added to the leaderboard"));
+        let leaderboard = Database::new(&database)
+            .unwrap()
+            .get_leaderboard(100)
+            .unwrap();
+        assert!(leaderboard.iter().any(|entry| entry.name == "Tester"));
+    }
+
+    #[test]
+    fn test_ambiguous_name_asks_which_one() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Godmode", "Go North", "hit rat", "2", "Quit"]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        assert!(transcript.contains("Which one do you mean?\n  1. Grote Rat\n  2. Grote Rat2\n"));
+        assert!(transcript.contains("Grote Rat2 took"));
+    }
+
+    #[test]
+    fn test_empty_and_chained_input() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "", "Go North; Go South then Look", "Quit"]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        let north = transcript.find("Going to 2").unwrap();
+        let south = transcript.find("Going to 1").unwrap();
+        assert!(north < south);
+        assert!(!transcript.contains("Not a valid command"));
+        assert!(transcript.ends_with("End Game\n"));
+    }
+
+    #[test]
+    fn test_take_and_put_several_items() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new([
+            "Tester",
+            "Search",
+            "Take all",
+            "Put all except dolk",
+            "Quit",
+        ]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        assert!(transcript.contains("Item 'klein levenselixer' has been moved to your backpack."));
+        assert!(
+            transcript.contains("Item 'klein levenselixer' has been removed from your backpack.")
+        );
+        assert!(!transcript.contains("has been removed from your hand"));
+    }
+
+    #[test]
+    fn test_help_for_one_command() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "help go", "Quit"]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        assert!(transcript.contains("Usage: Go <Direction>\n"));
+        assert!(transcript.contains("the enemies in the room attack you"));
+        assert!(transcript.contains("Right now: north\n"));
+    }
+
+    #[test]
+    fn test_dutch_messages_and_descriptions() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Look", "Help Go", "Dans", "Quit"]);
+        let options = GameOptions {
+            language: Language::Dutch,
+            ..GameOptions::default()
+        };
+        let mut game =
+            Game::with_options(options, Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        assert!(transcript.contains("Welkom bij kerkers en draken"));
+        assert!(transcript.contains("Je staat voor de poort van de ruine van een kasteel."));
+        assert!(transcript.contains("Uitgangen: noord (Binnenplaats)"));
+        assert!(transcript.contains("Gebruik: Go <Richting>\n"));
+        assert!(transcript.contains("Geen geldig commando\n"));
+        assert!(transcript.ends_with("Einde spel\n"));
+    }
+
+    #[test]
+    fn test_named_exits() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new([
+            "Tester", "Godmode", "n; e; n", "Look", "Go put", "Look", "Go up", "Quit",
+        ]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+
+        let transcript = output.contents();
+        assert!(transcript.contains("put (Bodem van de put)"));
+        let down = transcript.find("Going to 15\n").unwrap();
+        let up = transcript.rfind("Going to 9\n").unwrap();
+        assert!(down < up);
+        assert!(transcript.contains("Exits: touw (Waterput)"));
+    }
+
+    #[test]
+    fn test_color_is_opt_in() {
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Look", "Quit"]);
+        let options = GameOptions {
+            color: true,
+            ..GameOptions::default()
+        };
+        let mut game =
+            Game::with_options(options, Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+        assert!(output
+            .contents()
+            .contains("\x1b[1;36mKasteelpoort\x1b[0m\n"));
+
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Look", "Quit"]);
+        let mut game = Game::with_io(Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+        assert!(!output.contents().contains('\x1b'));
+    }
+
+    #[test]
+    fn test_games_on_one_thread_keep_their_own_console() {
+        let dutch_output = MemoryOutput::new();
+        let options = GameOptions {
+            language: Language::Dutch,
+            color: true,
+            ..GameOptions::default()
+        };
+        let input = MemoryInput::new(["Speler", "Look", "Quit"]);
+        let mut dutch =
+            Game::with_options(options, Box::new(input), Box::new(dutch_output.clone())).unwrap();
+        let english_output = MemoryOutput::new();
+        let input = MemoryInput::new(["Tester", "Look", "Quit"]);
+        let mut english = Game::with_io(Box::new(input), Box::new(english_output.clone())).unwrap();
+
+        dutch.print("voor het Nederlandse spel\n");
+        dutch.run();
+        english.run();
+
+        let dutch_output = dutch_output.contents();
+        let english_output = english_output.contents();
+        assert!(dutch_output.contains("voor het Nederlandse spel\n"));
+        assert!(dutch_output.contains("\x1b[1;36mKasteelpoort\x1b[0m\n"));
+        assert!(dutch_output.ends_with("Einde spel\n"));
+        assert!(!english_output.contains("voor het Nederlandse spel"));
+        assert!(!english_output.contains('\x1b'));
+        assert!(english_output.ends_with("End Game\n"));
     }
 }
@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use crate::{
+    console::console::Console, console_message, console_output, database::Database,
+    messages::Message,
+};
+
+pub(crate) const DEFAULT_LEADERBOARD_SIZE: usize = 10;
+
+pub(crate) fn record_score(console: &Console, database_path: &Path, name: &str, gold: u32) {
+    let db = match Database::new(database_path) {
+        Ok(db) => db,
+        Err(error) => {
+            console_message!(console, CouldNotRecordScore, error);
+            return;
+        }
+    };
+    match db.add_leaderboard_entry(name, gold) {
+        Ok(()) => {
+            console_message!(console, ScoreRecorded, name, gold);
+        }
+        Err(error) => {
+            console_message!(console, CouldNotRecordScore, error);
+        }
+    }
+}
+
+pub(crate) fn show_leaderboard(console: &Console, database_path: &Path, count: usize) {
+    let db = match Database::new(database_path) {
+        Ok(db) => db,
+        Err(error) => {
+            console_message!(console, CouldNotReadLeaderboard, error);
+            return;
+        }
+    };
+    match db.get_leaderboard(count) {
+        Ok(entries) if entries.is_empty() => {
+            console_message!(console, NoScores);
+        }
+        Ok(entries) => {
+            console_message!(console, LeaderboardHeader);
+            for (rank, entry) in entries.iter().enumerate() {
+                console_output!(
+                    console,
+                    "{:>3}. {:<20} {:>6} {}\n",
+                    rank + 1,
+                    entry.name,
+                    entry.gold,
+                    Message::Gold.text_in(console.language())
+                );
+            }
+        }
+        Err(error) => {
+            console_message!(console, CouldNotReadLeaderboard, error);
+        }
+    }
+}
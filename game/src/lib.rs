@@ -1,14 +1,30 @@
 mod command;
 mod database;
 mod enemy;
+mod error;
 mod game;
+mod leaderboard;
+mod messages;
 mod name_generator;
+mod name_resolver;
 mod objects;
+mod paths;
 mod player;
+mod presentation;
 mod room;
+mod save;
 mod story;
+#[cfg(test)]
+mod test_story;
 mod world;
 
 mod console;
 
-pub use game::Game;
+pub use command::{Argument, CommandHandler, CommandSpec, CommandSuggestions, Turn};
+pub use console::console::{
+    FileOutput, InputSource, LineEditor, MemoryInput, MemoryOutput, OutputSink, ScriptInput,
+    StdConsole,
+};
+pub use error::GameError;
+pub use game::{Game, GameOptions, SCRIPT_SEED};
+pub use messages::Language;
@@ -1,7 +1,133 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process,
+};
+
 use kerkers_en_draken::*;
 
+const USAGE: &str = "Usage: kerkers_en_draken [options]
+
+Options:
+  --story <file>       Story XML to play [env: KERKERS_STORY]
+  --database <file>    Object and enemy database [env: KERKERS_DATABASE]
+  --saves <directory>  Directory for Save and Load [env: KERKERS_SAVES]
+  --seed <number>      Seed for all random decisions, to replay an earlier run
+  --leaderboard        Print the best scores and exit
+  --check              Report problems in the story and database and exit,
+                       with a non-zero status if there are any
+  --script <file>      Read the player name and commands from <file>, one per line
+  --transcript <file>  Write all game output to <file> instead of the terminal
+  --no-color           Print plain text, also when NO_COLOR is set
+  --lang <language>    Language of the game, en (default) or nl";
+
+#[derive(Default)]
+struct Arguments {
+    story: Option<PathBuf>,
+    database: Option<PathBuf>,
+    saves: Option<PathBuf>,
+    seed: Option<u64>,
+    leaderboard: bool,
+    check: bool,
+    script: Option<String>,
+    transcript: Option<String>,
+    no_color: bool,
+    language: Language,
+}
+
+fn parse_arguments(mut args: impl Iterator<Item = String>) -> Result<Arguments, String> {
+    let mut arguments = Arguments::default();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--story" => arguments.story = Some(value()?.into()),
+            "--database" => arguments.database = Some(value()?.into()),
+            "--saves" => arguments.saves = Some(value()?.into()),
+            "--seed" => {
+                let seed = value()?;
+                arguments.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("Invalid seed: {}", seed))?,
+                );
+            }
+            "--leaderboard" => arguments.leaderboard = true,
+            "--check" => arguments.check = true,
+            "--script" => arguments.script = Some(value()?),
+            "--transcript" => arguments.transcript = Some(value()?),
+            "--no-color" => arguments.no_color = true,
+            "--lang" => {
+                let language = value()?;
+                arguments.language = Language::from_name(&language)
+                    .ok_or_else(|| format!("Unknown language: {}", language))?;
+            }
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    Ok(arguments)
+}
+
+/// Colors only make sense in a terminal, see https://no-color.org for `NO_COLOR`.
+fn use_color(arguments: &Arguments) -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !arguments.no_color && !no_color && arguments.transcript.is_none() && io::stdout().is_terminal()
+}
+
 fn main() {
+    let arguments = parse_arguments(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let color = use_color(&arguments);
+    let mut options = GameOptions {
+        seed: arguments.seed,
+        story_path: arguments.story,
+        database_path: arguments.database,
+        save_directory: arguments.saves,
+        // Scripted runs are for testing, keep them off the leaderboard.
+        record_score: arguments.script.is_none(),
+        color,
+        language: arguments.language,
+    };
+    if arguments.leaderboard {
+        Game::show_leaderboard(&options);
+        return;
+    }
+    if arguments.check {
+        let is_clean = Game::check_story(&options);
+        process::exit(if is_clean { 0 } else { 1 });
+    }
+
+    let input: Box<dyn InputSource> = match &arguments.script {
+        Some(path) => {
+            options.seed.get_or_insert(SCRIPT_SEED);
+            Box::new(ScriptInput::from_file(path).unwrap_or_else(|error| {
+                eprintln!("Could not read script {}: {}", path, error);
+                process::exit(1);
+            }))
+        }
+        None if io::stdin().is_terminal() => match LineEditor::new() {
+            Ok(editor) => Box::new(editor),
+            Err(_) => Box::new(StdConsole),
+        },
+        None => Box::new(StdConsole),
+    };
+    let output: Box<dyn OutputSink> = match &arguments.transcript {
+        Some(path) => Box::new(FileOutput::create(path).unwrap_or_else(|error| {
+            eprintln!("Could not create transcript {}: {}", path, error);
+            process::exit(1);
+        })),
+        None => Box::new(StdConsole),
+    };
+
     println!("Hello, world!");
-    let mut game = Game::new();
+    let mut game = Game::with_options(options, input, output).unwrap_or_else(|error| {
+        eprintln!("Could not start the game: {}", error);
+        process::exit(1);
+    });
     game.run();
 }
@@ -0,0 +1,480 @@
+//! Everything the engine says to the player, in English and Dutch. Story content such as room
+//! and object descriptions comes from the story and database instead, and the problems
+//! `--check` reports are meant for story authors and stay in English.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The language of the messages, picked with `--lang`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Dutch,
+}
+
+impl Language {
+    /// Accepts the language code, as used by `--lang` and the `taal` attribute in a story,
+    /// and the name of the language in English or Dutch, e.g. "nl", "Dutch" or "nederlands".
+    pub fn from_name(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "en" | "english" | "engels" => Some(Language::English),
+            "nl" | "dutch" | "nederlands" => Some(Language::Dutch),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Dutch => "nl",
+        }
+    }
+}
+
+/// Anything that knows which language to say a `Message` in, see `message!`.
+pub(crate) trait Localized {
+    fn language(&self) -> Language;
+}
+
+impl Localized for Language {
+    fn language(&self) -> Language {
+        *self
+    }
+}
+
+impl<T: Localized + ?Sized> Localized for &T {
+    fn language(&self) -> Language {
+        (**self).language()
+    }
+}
+
+macro_rules! catalog {
+    ($($id:ident => $english:literal, $dutch:literal;)*) => {
+        /// The id of a message in the catalog.
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub(crate) enum Message {
+            $($id,)*
+        }
+
+        impl Message {
+            #[cfg(test)]
+            const ALL: &'static [Message] = &[$(Message::$id,)*];
+
+            /// The message in `language`, with `{}` or `{0}`, `{1}`, .. where its arguments go.
+            pub(crate) fn text_in(self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                        (Message::$id, Language::English) => $english,
+                        (Message::$id, Language::Dutch) => $dutch,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    Welcome =>
+        "Welcome to kerkers en draken. A simple text adventure game made in rust for a school project learning rust \n",
+        "Welkom bij kerkers en draken. Een eenvoudig tekstavontuur, in rust gemaakt voor een schoolproject om rust te leren \n";
+    Instructions =>
+        "Everytime the prompt shows '>' insert a command. if player does not know any commands insert Help\n",
+        "Typ een commando als de prompt '>' toont. Typ Help als je de commando's nog niet kent\n";
+    Seed => "Seed: {}", "Seed: {}";
+    NotACommand => "Not a valid command\n", "Geen geldig commando\n";
+    Usage => "Usage: {}\n", "Gebruik: {}\n";
+    WhichOne => "Which one do you mean?\n", "Welke bedoel je?\n";
+    NoneOfThem => "'{}' is none of them.\n", "'{}' is geen van allen.\n";
+    NothingMatches => "There is nothing that matches '{}'.\n", "Er is niets dat past bij '{}'.\n";
+    EndGame => "End Game\n", "Einde spel\n";
+
+    PlayerDetails =>
+        "Player Information:
+--------------------
+Name:           {}
+HP:             {}
+Gold:           {}
+Attack Chance:  {}%
+Weight:         {}
+Weapon:         {}
+Armor:          {}
+Backpack:       {}
+--------------------\n",
+        "Spelerinformatie:
+--------------------
+Naam:           {}
+HP:             {}
+Goud:           {}
+Aanvalskans:    {}%
+Gewicht:        {}
+Wapen:          {}
+Wapenrusting:   {}
+Rugzak:         {}
+--------------------\n";
+    CarriedWeight => "{}/{}", "{}/{}";
+    EncumberedWeight => "{}/{}, encumbered", "{}/{}, overbelast";
+    TooHeavy =>
+        "Item '{}' is too heavy, you carry {} of at most {}.\n",
+        "Voorwerp '{}' is te zwaar, je draagt {} van hoogstens {}.\n";
+    Encumbered =>
+        "You carry so much that your attack chance drops by {}%.\n",
+        "Je draagt zoveel dat je aanvalskans {}% lager is.\n";
+    NoEquipment => "None", "Geen";
+    EmptyBackpack => "Empty", "Leeg";
+    PlayerDamage => "player does total damage {} \n", "speler doet in totaal {} schade \n";
+    PlayerUnarmed => "player has no weapon does 0 damage \n", "speler heeft geen wapen en doet 0 schade \n";
+    PlayerMisses => "player misses\n", "speler mist\n";
+    PlayerInGodmode =>
+        "Player is in godmode. it cannot take damage in this state\n",
+        "Speler staat in godmode en kan zo geen schade oplopen\n";
+    DamageAfterArmor =>
+        "You took {} damage after armor mitigation. Your current HP: {}\n",
+        "Je liep {} schade op na je wapenrusting. Je HP is nu: {}\n";
+    DamageTaken => "You took {} damage. Your current HP: {}\n", "Je liep {} schade op. Je HP is nu: {}\n";
+    CoinPickup => "Coin pickup {} player has now {} \n", "{} goud opgepakt, speler heeft nu {} \n";
+    WeaponRemoved => "Weapon '{}' has been removed from your hand.\n", "Wapen '{}' is uit je hand genomen.\n";
+    ArmorRemoved => "Armor '{}' has been removed from your body.\n", "Wapenrusting '{}' is uitgetrokken.\n";
+    ItemRemoved => "Item '{}' has been removed from your backpack.\n", "Voorwerp '{}' is uit je rugzak genomen.\n";
+    ItemNotCarried =>
+        "Item '{}' not found in weapon, armor, or backpack.\n",
+        "Voorwerp '{}' niet gevonden bij je wapen, wapenrusting of rugzak.\n";
+    WeaponSwitched => "Switched out weapon.\n", "Wapen gewisseld.\n";
+    WeaponEquipped => "Equipping new weapon.\n", "Nieuw wapen in de hand.\n";
+    ArmorSwitched => "Switched out armor.\n", "Wapenrusting gewisseld.\n";
+    ArmorEquipped => "Equipped new armor \n", "Nieuwe wapenrusting aangetrokken \n";
+    NotEquippable => "Item '{}' is not equippable.\n", "Voorwerp '{}' kun je niet dragen.\n";
+    NotInBackpack => "Item '{}' not found in backpack.\n", "Voorwerp '{}' niet gevonden in je rugzak.\n";
+    PotionConsumed => "Potion is consumed:\n", "Drankje is opgedronken:\n";
+    NewHealth => "New health: {} \n", "Nieuwe gezondheid: {} \n";
+    NewAttackChance => "New attack chance: {}% \n", "Nieuwe aanvalskans: {}% \n";
+
+    EnemyDetails =>
+        "\n Enemy Details:
+--------------------
+Name:           {}
+Description:    {}
+HP:             {}
+Damage Range:   {} - {}
+Room ID:        {}
+Invisible Items: {}
+--------------------\n",
+        "\n Vijandinformatie:
+--------------------
+Naam:           {}
+Beschrijving:   {}
+HP:             {}
+Schade:         {} - {}
+Locatie:        {}
+Verborgen voorwerpen: {}
+--------------------\n";
+    EnemyHits => "{} hits the player for {}\n", "{} raakt de speler voor {}\n";
+    EnemyMisses => "{} misses the player\n", "{} mist de speler\n";
+    EnemyAlreadyDead => "Enemy: {} is already dead\n", "Vijand: {} is al dood\n";
+    EnemyWounded => "{} took {} damage, {} HP remaining.\n", "{} liep {} schade op, nog {} HP over.\n";
+    EnemyDied => "{} took {} damage and has died.\n", "{} liep {} schade op en is gestorven.\n";
+    NoEnemyNamed => "No enemies found with that name: {}\n", "Geen vijanden gevonden met de naam: {}\n";
+
+    RoomItems => "\nItems: {}", "\nVoorwerpen: {}";
+    RoomExits => "\nExits: {}", "\nUitgangen: {}";
+    NoExits => "\nExits: none", "\nUitgangen: geen";
+    ItemTaken => "Item '{}' has been moved to your backpack.\n", "Voorwerp '{}' zit nu in je rugzak.\n";
+    ItemNotInRoom =>
+        "Item '{}' not found in the room's visible items.\n",
+        "Voorwerp '{}' niet gevonden tussen de zichtbare voorwerpen.\n";
+    North => "north", "noord";
+    South => "south", "zuid";
+    East => "east", "oost";
+    West => "west", "west";
+    Up => "up", "omhoog";
+    Down => "down", "omlaag";
+    NorthEast => "northeast", "noordoost";
+    NorthWest => "northwest", "noordwest";
+    SouthEast => "southeast", "zuidoost";
+    SouthWest => "southwest", "zuidwest";
+    Nowhere => "nowhere", "nergens";
+    Teleported => "You are teleported {} locations away\n", "Je wordt {} locaties verder geteleporteerd\n";
+    GoingTo => "Going to {}\n", "Op weg naar {}\n";
+    WrongDirection => "Wrong goto direction\n", "Die kant kun je niet op\n";
+    Locked => "locked", "op slot";
+    ExitLocked => "The exit {} is locked.\n", "De uitgang {} is op slot.\n";
+    ExitNeedsItem =>
+        "You cannot take the exit {} without {}.\n",
+        "Je kunt de uitgang {} niet nemen zonder {}.\n";
+    ExitGuarded => "{} blocks the exit {}.\n", "{} verspert de uitgang {}.\n";
+    ExitClosed => "The exit {} is closed.\n", "De uitgang {} is gesloten.\n";
+    HiddenExitFound => "You found a hidden exit: {}\n", "Je hebt een verborgen uitgang gevonden: {}\n";
+    ExitNotLocked => "The exit {} is not locked.\n", "De uitgang {} is niet op slot.\n";
+    TheExit => "the exit {}", "de uitgang {}";
+    NoKey => "You have nothing that unlocks {}.\n", "Je hebt niets om {} mee te ontgrendelen.\n";
+    WrongKey => "{} does not fit the lock of {}.\n", "{} past niet op het slot van {}.\n";
+    Unlocked => "You unlock {} with {}.\n", "Je ontgrendelt {} met {}.\n";
+    NoContainer => "There is no container '{}' here.\n", "Er is hier geen houder '{}'.\n";
+    ContainerLocked => "Container '{}' is locked.\n", "Houder '{}' is op slot.\n";
+    ContainerNotLocked => "Container '{}' is not locked.\n", "Houder '{}' is niet op slot.\n";
+    ContainerContents => "Container '{}' contains {}.\n", "Houder '{}' bevat {}.\n";
+    ContainerFixed => "Container '{}' is too heavy to carry.\n", "Houder '{}' is te zwaar om te dragen.\n";
+    ContainerInItself => "Container '{}' cannot go in itself.\n", "Houder '{}' kan niet in zichzelf.\n";
+    ItemNotInContainer => "Item '{}' not found in '{}'.\n", "Voorwerp '{}' niet gevonden in '{}'.\n";
+    ItemPutIn => "Item '{}' has been put in '{}'.\n", "Voorwerp '{}' zit nu in '{}'.\n";
+
+    ItemDetails =>
+        "Item Details:
+Name: {}
+Description: {}
+Weight: {}
+Details: ",
+        "Voorwerpinformatie:
+Naam: {}
+Beschrijving: {}
+Gewicht: {}
+Details: ";
+    CoinSummary => "worth {} gold", "{} goud waard";
+    WeaponSummary => "damage {}-{}", "schade {}-{}";
+    ArmorSummary => "defense {}", "verdediging {}";
+    HealSummary => "heals {}", "geneest {}";
+    TeleportSummary => "teleports {}-{} locations", "teleporteert {}-{} locaties";
+    ExperienceSummary => "attack chance +{}%", "aanvalskans +{}%";
+    CoinDetails => "pouch has amount {}\n", "buidel bevat {}\n";
+    WeaponDetails => "Weapon has damage range {}-{}\n", "Wapen doet {}-{} schade\n";
+    ArmorDetails => "Armor has defense of {}\n", "Wapenrusting heeft verdediging {}\n";
+    HealDetails => "Potion has heals {}\n", "Drankje geneest {}\n";
+    TeleportDetails => "Potion teleports {}-{} locations away\n", "Drankje teleporteert {}-{} locaties ver\n";
+    ExperienceDetails =>
+        "Potion raises attack chance by {}%\n",
+        "Drankje verhoogt de aanvalskans met {}%\n";
+    ContainerSummary => "holds {} items", "bevat {} voorwerpen";
+    ContainerDetails => "Contains {}\n", "Bevat {}\n";
+    ContainerLockedDetails => "It is locked\n", "Het is op slot\n";
+    ListAnd => "{} and {}", "{} en {}";
+    Nothing => "nothing", "niets";
+
+    NoSuchCommand => "There is no command '{}', see Help\n", "Er is geen commando '{}', zie Help\n";
+    CommandsHeader => "Commands:\n", "Commando's:\n";
+    ChainingHint =>
+        "Commands can be chained with ';' or 'then'. Help <Command> tells more.\n",
+        "Commando's kun je achter elkaar zetten met ';' of 'dan'. Help <Commando> vertelt meer.\n";
+    RightNowHeader => "\nRight now you can:\n", "\nNu kun je:\n";
+    AlsoVerbs => "Also: {}\n", "Ook: {}\n";
+    ExamplesHeader => "Examples:\n", "Voorbeelden:\n";
+    NothingToUseOn => "Nothing to use it on right now.\n", "Er is nu niets om het op te gebruiken.\n";
+    RightNow => "Right now: {}\n", "Nu: {}\n";
+    TurnFree => "Free, the enemies do not get a turn.", "Gratis, de vijanden krijgen geen beurt.";
+    TurnEnemiesAttack =>
+        "Takes a turn, the enemies in the room attack you.",
+        "Kost een beurt, de vijanden in de ruimte vallen je aan.";
+    TurnEnemiesAttackAndMove =>
+        "Takes a turn, the enemies in the room attack you and then all enemies may move.",
+        "Kost een beurt, de vijanden in de ruimte vallen je aan en daarna kunnen alle vijanden verplaatsen.";
+    ArgumentObject => "Object", "Voorwerp";
+    ArgumentDirection => "Direction", "Richting";
+    ArgumentEnemy => "Enemy", "Vijand";
+    ArgumentCommand => "Command", "Commando";
+    ArgumentCount => "Count", "Aantal";
+    ArgumentExit => "Exit", "Uitgang";
+    ArgumentContainer => "Container", "Houder";
+    ArgumentSlot => "Slot", "Slot";
+    HelpHelp =>
+        "Show the commands you can use, or everything about one of them",
+        "Toon de commando's die je kunt gebruiken, of alles over een ervan";
+    HelpLook => "Describe the room, its items, exits and enemies", "Beschrijf de ruimte, de voorwerpen, uitgangen en vijanden";
+    HelpLookIn => "Show what is in a container", "Toon wat er in een houder zit";
+    HelpSearch =>
+        "Search the room for hidden items and exits",
+        "Doorzoek de ruimte naar verborgen voorwerpen en uitgangen";
+    HelpUnlock =>
+        "Unlock an exit or container with a key you carry, e.g. 'north with dolk'",
+        "Ontgrendel een uitgang of houder met een sleutel die je draagt, bv. 'noord met dolk'";
+    HelpOpen =>
+        "Open a container to see what is in it, or a locked exit",
+        "Open een houder om te zien wat erin zit, of een uitgang op slot";
+    HelpGo =>
+        "Walk through an exit, e.g. North, n, up or the name of an exit",
+        "Loop door een uitgang, bijvoorbeeld Noord, n, omhoog of de naam van een uitgang";
+    HelpTake =>
+        "Pick up items in the room, also 'all', a list or 'from' a container",
+        "Pak voorwerpen in de ruimte op, ook 'alles', een lijst of 'uit' een houder";
+    HelpPut =>
+        "Drop carried items in the room, also 'all', a list or 'in' a container",
+        "Leg voorwerpen die je draagt neer, ook 'alles', een lijst of 'in' een houder";
+    HelpSee => "Look at an enemy, a dead one drops its items", "Bekijk een vijand, een dode laat zijn voorwerpen vallen";
+    HelpSeePlayer => "Show your health, gold, equipment and backpack", "Toon je gezondheid, goud, uitrusting en rugzak";
+    HelpHit => "Attack an enemy in the room", "Val een vijand in de ruimte aan";
+    HelpWear => "Equip a weapon or armor from your backpack", "Neem een wapen of wapenrusting uit je rugzak in gebruik";
+    HelpWait => "Let the enemies take a turn", "Laat de vijanden een beurt doen";
+    HelpConsume => "Drink potions from your backpack, also 'all' or a list", "Drink drankjes uit je rugzak, ook 'alles' of een lijst";
+    HelpGodmode => "Toggle invulnerability", "Zet onkwetsbaarheid aan of uit";
+    HelpSave => "Save the game in a slot", "Sla het spel op in een slot";
+    HelpLoad => "Continue a game saved in a slot", "Ga verder met een spel uit een slot";
+    HelpLeaderboard => "Show the best scores", "Toon de beste scores";
+    HelpQuit => "End the game", "Stop het spel";
+
+    GameSaved => "Game saved to {}\n", "Spel opgeslagen in {}\n";
+    CouldNotSave => "Could not save game: {}\n", "Kon het spel niet opslaan: {}\n";
+    GameLoaded => "Game loaded from slot '{}'\n", "Spel geladen uit slot '{}'\n";
+    CouldNotLoad => "Could not load game: {}\n", "Kon het spel niet laden: {}\n";
+    InvalidSlot =>
+        "'{}' is not a valid slot name, use letters, digits, '-' or '_'",
+        "'{}' is geen geldige slotnaam, gebruik letters, cijfers, '-' of '_'";
+    CorruptSave => "save file is corrupt: {}", "opgeslagen spel is beschadigd: {}";
+    SaveWithoutVersion => "save file has no format version", "opgeslagen spel heeft geen formaatversie";
+    SaveWithoutWorld => "save file has no world", "opgeslagen spel heeft geen wereld";
+    SaveWithUnknownLocation =>
+        "save file refers to location {}, which is not in it",
+        "opgeslagen spel verwijst naar locatie {}, die er niet in staat";
+    UnsupportedSaveVersion =>
+        "save format version {} is newer than the supported version {}",
+        "formaatversie {} is nieuwer dan de ondersteunde versie {}";
+
+    CouldNotRecordScore => "Could not record score: {}\n", "Kon de score niet bewaren: {}\n";
+    ScoreRecorded => "{} is added to the leaderboard with {} gold\n", "{} staat nu op het scorebord met {} goud\n";
+    CouldNotReadLeaderboard => "Could not read leaderboard: {}\n", "Kon het scorebord niet lezen: {}\n";
+    NoScores => "No scores recorded yet\n", "Nog geen scores\n";
+    LeaderboardHeader => "Leaderboard:\n", "Scorebord:\n";
+    Gold => "gold", "goud";
+}
+
+impl Message {
+    /// The help line of a built-in command, by its name.
+    pub(crate) fn command_help(name: &str) -> Option<Self> {
+        let message = match name {
+            "Help" => Message::HelpHelp,
+            "Look" => Message::HelpLook,
+            "LookIn" => Message::HelpLookIn,
+            "Search" => Message::HelpSearch,
+            "Unlock" => Message::HelpUnlock,
+            "Open" => Message::HelpOpen,
+            "Go" => Message::HelpGo,
+            "Take" => Message::HelpTake,
+            "Put" => Message::HelpPut,
+            "See" => Message::HelpSee,
+            "SeePlayer" => Message::HelpSeePlayer,
+            "Hit" => Message::HelpHit,
+            "Wear" => Message::HelpWear,
+            "Wait" => Message::HelpWait,
+            "Consume" => Message::HelpConsume,
+            "Godmode" => Message::HelpGodmode,
+            "Save" => Message::HelpSave,
+            "Load" => Message::HelpLoad,
+            "Leaderboard" => Message::HelpLeaderboard,
+            "Quit" => Message::HelpQuit,
+            _ => return None,
+        };
+        Some(message)
+    }
+
+    /// The name of an argument of the built-in commands, e.g. "Object".
+    pub(crate) fn argument_name(name: &str) -> Option<Self> {
+        let message = match name {
+            "Object" => Message::ArgumentObject,
+            "Direction" => Message::ArgumentDirection,
+            "Enemy" => Message::ArgumentEnemy,
+            "Command" => Message::ArgumentCommand,
+            "Count" => Message::ArgumentCount,
+            "Exit" => Message::ArgumentExit,
+            "Container" => Message::ArgumentContainer,
+            "Slot" => Message::ArgumentSlot,
+            _ => return None,
+        };
+        Some(message)
+    }
+}
+
+/// Puts `args` in `template`: `{}` takes the next argument, `{1}` the second one, so a
+/// translation can change their order.
+pub(crate) fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        // An unmatched '{' is plain text, the rest is appended after the loop.
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        text.push_str(&rest[..open]);
+        let index = match &rest[open + 1..open + close] {
+            "" => {
+                next += 1;
+                next - 1
+            }
+            number => number.parse().unwrap_or(usize::MAX),
+        };
+        match args.get(index) {
+            Some(arg) => text.push_str(&arg.to_string()),
+            None => text.push_str(&rest[open..=open + close]),
+        }
+        rest = &rest[open + close + 1..];
+    }
+    text.push_str(rest);
+    text
+}
+
+/// The text of a `Message` with its arguments in the language of a `Localized`, e.g.
+/// `message!(console, GoingTo, room)`.
+#[macro_export]
+macro_rules! message {
+    ($language:expr, $id:ident $(, $arg:expr)* $(,)?) => {
+        $crate::messages::fill(
+            $crate::messages::Message::$id
+                .text_in($crate::messages::Localized::language(&$language)),
+            &[$(&$arg as &dyn std::fmt::Display),*],
+        )
+    };
+}
+
+/// Prints a `Message` with its arguments to a `Console`, see `message!`.
+#[macro_export]
+macro_rules! console_message {
+    ($console:expr, $($arg:tt)*) => {
+        $crate::console_output!($console, "{}", $crate::message!($console, $($arg)*));
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(template: &str) -> Vec<&str> {
+        let mut found: Vec<&str> = template
+            .match_indices('{')
+            .filter_map(|(open, _)| {
+                template[open..]
+                    .find('}')
+                    .map(|close| &template[open..=open + close])
+            })
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_translations_take_the_same_arguments() {
+        for message in Message::ALL {
+            let english = message.text_in(Language::English);
+            let dutch = message.text_in(Language::Dutch);
+            assert!(!english.is_empty() && !dutch.is_empty(), "{:?}", message);
+            assert_eq!(placeholders(english), placeholders(dutch), "{:?}", message);
+        }
+    }
+
+    #[test]
+    fn test_fill_in_order_and_by_position() {
+        assert_eq!(fill("{} and {}", &[&"dolk", &2]), "dolk and 2");
+        assert_eq!(fill("{1} voor {0}", &[&"a", &"b"]), "b voor a");
+        assert_eq!(fill("no {} args", &[]), "no {} args");
+    }
+
+    #[test]
+    fn test_fill_unmatched_brace() {
+        assert_eq!(fill("a {b", &[]), "a {b");
+        assert_eq!(fill("{} and {", &[&"dolk"]), "dolk and {");
+    }
+
+    #[test]
+    fn test_language_codes_and_names() {
+        assert_eq!(Language::from_name("nl"), Some(Language::Dutch));
+        assert_eq!(Language::from_name("English"), Some(Language::English));
+        assert_eq!(Language::from_name("fr"), None);
+    }
+}
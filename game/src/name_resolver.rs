@@ -0,0 +1,212 @@
+use crate::objects::{Item, ItemCategory};
+
+/// What a name typed by the player refers to among the names in reach.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Resolution {
+    Found(String),
+    NotFound,
+    /// Several names match, in the order they were given.
+    Ambiguous(Vec<String>),
+}
+
+const ORDINALS: [[&str; 2]; 10] = [
+    ["first", "eerste"],
+    ["second", "tweede"],
+    ["third", "derde"],
+    ["fourth", "vierde"],
+    ["fifth", "vijfde"],
+    ["sixth", "zesde"],
+    ["seventh", "zevende"],
+    ["eighth", "achtste"],
+    ["ninth", "negende"],
+    ["tenth", "tiende"],
+];
+
+/// Matches `query` against `candidates` ignoring case. A full name wins outright, otherwise
+/// every word of the query has to start a word of the name, so "rat" matches "Grote Rat2".
+/// An ordinal in front ("second rat") or a number at the end ("rat 2") picks one of several
+/// matches.
+pub(crate) fn resolve_name(query: &str, candidates: &[String]) -> Resolution {
+    let query = query.trim().to_lowercase();
+    if let Some(name) = candidates
+        .iter()
+        .find(|candidate| candidate.to_lowercase() == query)
+    {
+        return Resolution::Found(name.clone());
+    }
+
+    let mut words: Vec<&str> = query.split_whitespace().collect();
+    let mut choice = None;
+    if let Some(number) = words.first().and_then(|word| ordinal(word)) {
+        choice = Some(number);
+        words.remove(0);
+    } else if let Some(number) = words.last().and_then(|word| word.parse::<usize>().ok()) {
+        choice = Some(number);
+        words.pop();
+    }
+
+    let matches: Vec<&String> = candidates
+        .iter()
+        .filter(|candidate| matches_words(candidate, &words))
+        .collect();
+    match (matches.len(), choice) {
+        (0, _) => Resolution::NotFound,
+        (_, Some(number)) => match number.checked_sub(1).and_then(|idx| matches.get(idx)) {
+            Some(name) => Resolution::Found(name.to_string()),
+            None => Resolution::NotFound,
+        },
+        (1, None) => Resolution::Found(matches[0].clone()),
+        _ => Resolution::Ambiguous(matches.into_iter().cloned().collect()),
+    }
+}
+
+/// The items a command names: "all", "all potions", "all except dolk" or a list such as
+/// "dolk, speer". Dutch "alles" and "behalve" work as well.
+#[derive(Debug, PartialEq)]
+pub(crate) enum ItemSelection {
+    All {
+        category: Option<ItemCategory>,
+        except: Vec<String>,
+    },
+    Listed(Vec<String>),
+}
+
+impl ItemSelection {
+    pub(crate) fn parse(input: &str) -> Self {
+        let mut words = input.split_whitespace();
+        let is_all = words.next().is_some_and(|word| {
+            ["all", "alles", "everything"].contains(&word.to_lowercase().as_str())
+        });
+        if !is_all {
+            return ItemSelection::Listed(split_list(input));
+        }
+
+        let mut rest: Vec<&str> = words.collect();
+        let category = rest.first().and_then(|word| ItemCategory::from_str(word));
+        if category.is_some() {
+            rest.remove(0);
+        }
+        let except = match rest.split_first() {
+            Some((word, names))
+                if ["except", "behalve", "but"].contains(&word.to_lowercase().as_str()) =>
+            {
+                split_list(&names.join(" "))
+            }
+            Some(_) => return ItemSelection::Listed(split_list(input)),
+            None => Vec::new(),
+        };
+        ItemSelection::All { category, except }
+    }
+}
+
+/// The name and category of each of `items`, to choose from with an `ItemSelection`.
+pub(crate) fn item_candidates<'a>(
+    items: impl IntoIterator<Item = &'a Item>,
+) -> Vec<(String, ItemCategory)> {
+    items
+        .into_iter()
+        .map(|item| (item.name().to_string(), item.category()))
+        .collect()
+}
+
+fn split_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn matches_words(candidate: &str, words: &[&str]) -> bool {
+    let candidate = candidate.to_lowercase();
+    let name_words: Vec<&str> = candidate.split_whitespace().collect();
+    words.iter().all(|word| {
+        name_words
+            .iter()
+            .any(|name_word| name_word.starts_with(word))
+    })
+}
+
+/// "second", "tweede", "2nd" and "2" all give 2.
+fn ordinal(word: &str) -> Option<usize> {
+    if let Some(position) = ORDINALS.iter().position(|names| names.contains(&word)) {
+        return Some(position + 1);
+    }
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if digits.len() < word.len() {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_case_insensitive_and_prefix_matches() {
+        let candidates = names(&["dolk", "klein levenselixer", "Goblin"]);
+        assert_eq!(
+            resolve_name("goblin", &candidates),
+            Resolution::Found("Goblin".to_string())
+        );
+        assert_eq!(
+            resolve_name("kl lev", &candidates),
+            Resolution::Found("klein levenselixer".to_string())
+        );
+        assert_eq!(resolve_name("zwaard", &candidates), Resolution::NotFound);
+    }
+
+    #[test]
+    fn test_several_matches_are_ambiguous() {
+        let candidates = names(&["Grote Rat", "Grote Rat2", "Goblin"]);
+        assert_eq!(
+            resolve_name("rat", &candidates),
+            Resolution::Ambiguous(names(&["Grote Rat", "Grote Rat2"]))
+        );
+        assert_eq!(
+            resolve_name("grote rat", &candidates),
+            Resolution::Found("Grote Rat".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_item_selection() {
+        assert_eq!(
+            ItemSelection::parse("all"),
+            ItemSelection::All {
+                category: None,
+                except: Vec::new()
+            }
+        );
+        assert_eq!(
+            ItemSelection::parse("All potions except klein levenselixer, dolk"),
+            ItemSelection::All {
+                category: Some(ItemCategory::Potion),
+                except: names(&["klein levenselixer", "dolk"])
+            }
+        );
+        assert_eq!(
+            ItemSelection::parse("dolk, kleine buidel"),
+            ItemSelection::Listed(names(&["dolk", "kleine buidel"]))
+        );
+    }
+
+    #[test]
+    fn test_ordinals_and_numbers_pick_a_match() {
+        let candidates = names(&["Grote Rat", "Grote Rat2"]);
+        let second = Resolution::Found("Grote Rat2".to_string());
+        assert_eq!(resolve_name("second rat", &candidates), second);
+        assert_eq!(resolve_name("tweede rat", &candidates), second);
+        assert_eq!(resolve_name("rat 2", &candidates), second);
+        assert_eq!(resolve_name("2nd", &candidates), second);
+        assert_eq!(resolve_name("2", &candidates), second);
+        assert_eq!(resolve_name("third rat", &candidates), Resolution::NotFound);
+    }
+}
@@ -1,48 +1,168 @@
+use std::fmt;
+
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::console_output;
+use crate::{
+    console::console::Console,
+    console_message, message,
+    messages::Language,
+    presentation::{base_name, item_list},
+    world::GameRng,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct GameObject {
     name: String,
     description: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum GameObjectType {
     Coin(Coin),
     Weapon(Weapon),
     Armor(Armor),
     Consumable(Consumable),
+    Teleport(Teleport),
+    Experience(Experience),
+    Container(Container),
+}
+
+/// The groups of items a player can name at once, as in "Take all potions".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ItemCategory {
+    Coin,
+    Weapon,
+    Armor,
+    Potion,
+    Container,
+}
+
+impl ItemCategory {
+    pub(crate) fn from_str(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "coins" | "coin" | "gold" | "goud" | "geld" => Some(ItemCategory::Coin),
+            "weapons" | "weapon" | "wapens" | "wapen" => Some(ItemCategory::Weapon),
+            "armor" | "armour" | "wapenrusting" | "wapenrustingen" => Some(ItemCategory::Armor),
+            "potions" | "potion" | "drankjes" | "drankje" | "dranken" => Some(ItemCategory::Potion),
+            "containers" | "container" | "kisten" | "tassen" => Some(ItemCategory::Container),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Item {
     base: GameObject,
     kind: GameObjectType,
+    /// Weight of the item itself, see `weight` for what the player has to carry.
+    weight: u32,
+    /// The other units when identical potions are stacked, see `stacks_with`. Each keeps
+    /// the value it rolled, the top one is `kind`.
+    stack: Vec<GameObjectType>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Coin {
     value: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Weapon {
     minimum_damage: u32,
     maximum_damage: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Armor {
     defense: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Consumable {
     heal_amount: u32,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Experience {
+    attack_chance_bonus: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Teleport {
+    minimum_locations: u32,
+    maximum_locations: u32,
+}
+
+/// An item that holds other items, such as a chest or a pouch. Containers are declared in
+/// the story, not in the database.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Container {
+    contents: Vec<Item>,
+    /// The name of the item that unlocks the container, while it is locked.
+    lock: Option<String>,
+    /// Fixed containers, like a chest, stay in the room.
+    fixed: bool,
+}
+
+impl Container {
+    pub(crate) fn contents(&self) -> &[Item] {
+        &self.contents
+    }
+
+    pub(crate) fn lock(&self) -> Option<&str> {
+        self.lock.as_deref()
+    }
+
+    pub(crate) fn unlock(&mut self) {
+        self.lock = None;
+    }
+
+    pub(crate) fn is_fixed(&self) -> bool {
+        self.fixed
+    }
+
+    pub(crate) fn insert(&mut self, item: Item) {
+        stack_into(&mut self.contents, item);
+    }
+
+    /// Takes the item out, or only `count` of it when it is a bigger stack.
+    pub(crate) fn remove(&mut self, item_name: &str, count: Option<u32>) -> Option<Item> {
+        take_units(&mut self.contents, item_name, count)
+    }
+}
+
+/// Adds `item` to `items`, on the stack it belongs to when there is one.
+pub(crate) fn stack_into(items: &mut Vec<Item>, item: Item) {
+    match items.iter_mut().find(|other| other.stacks_with(&item)) {
+        Some(stack) => stack.merge(item),
+        None => items.push(item),
+    }
+}
+
+/// The items with identical potions stacked, see `stack_into`.
+pub(crate) fn stacked(items: &[Item]) -> Vec<Item> {
+    let mut stacks = Vec::new();
+    for item in items {
+        stack_into(&mut stacks, item.clone());
+    }
+    stacks
+}
+
+/// Takes the item called `item_name` out of `items`, or only `count` of it when it is a
+/// bigger stack.
+pub(crate) fn take_units(
+    items: &mut Vec<Item>,
+    item_name: &str,
+    count: Option<u32>,
+) -> Option<Item> {
+    let position = items.iter().position(|item| item.name() == item_name)?;
+    match count {
+        Some(count) if count < items[position].count() => Some(items[position].split_off(count)),
+        _ => Some(items.remove(position)),
+    }
+}
+
 impl Item {
     pub(crate) fn name(&self) -> &str {
         &self.base.name
@@ -52,62 +172,258 @@ impl Item {
         &self.kind
     }
 
-    pub(crate) fn do_action(&self) -> u32 {
-        self.kind.do_action()
+    pub(crate) fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// The weight of the item with everything in it, or of the whole stack.
+    pub(crate) fn weight(&self) -> u32 {
+        let contents = self.container().map_or(0, |container| {
+            container.contents().iter().map(Item::weight).sum()
+        });
+        self.weight * self.count() + contents
+    }
+
+    /// The weight of `count` units of the stack, or of all of it when `None`.
+    pub(crate) fn units_weight(&self, count: Option<u32>) -> u32 {
+        match count {
+            Some(count) if count < self.count() => self.weight * count,
+            _ => self.weight(),
+        }
+    }
+
+    /// How many units the item stands for, 1 unless it is a stack.
+    pub(crate) fn count(&self) -> u32 {
+        1 + self.stack.len() as u32
+    }
+
+    /// Whether `other` belongs on this item's stack: potions of the same kind with the same
+    /// name, apart from the number `NameGenerator` added.
+    pub(crate) fn stacks_with(&self, other: &Item) -> bool {
+        self.category() == ItemCategory::Potion
+            && std::mem::discriminant(&self.kind) == std::mem::discriminant(&other.kind)
+            && base_name(self.name()) == base_name(other.name())
+    }
+
+    /// Puts the units of `other` on this stack, which is then named without a number.
+    pub(crate) fn merge(&mut self, other: Item) {
+        self.base.name = base_name(&self.base.name).to_string();
+        self.stack.push(other.kind);
+        self.stack.extend(other.stack);
+    }
+
+    /// Takes `count` units off the stack as an item of their own, `count` has to be less
+    /// than `self.count()`.
+    pub(crate) fn split_off(&mut self, count: u32) -> Item {
+        let mut units = self.stack.split_off(self.stack.len() - count as usize);
+        let kind = units.remove(0);
+        Item {
+            base: self.base.clone(),
+            kind,
+            weight: self.weight,
+            stack: units,
+        }
+    }
+
+    /// The name with the number of units in front for a stack, e.g. "3 x klein levenselixer".
+    pub(crate) fn label(&self) -> String {
+        match self.count() {
+            1 => self.name().to_string(),
+            count => format!("{} x {}", count, self.name()),
+        }
+    }
+
+    pub(crate) fn category(&self) -> ItemCategory {
+        match self.kind {
+            GameObjectType::Coin(_) => ItemCategory::Coin,
+            GameObjectType::Weapon(_) => ItemCategory::Weapon,
+            GameObjectType::Armor(_) => ItemCategory::Armor,
+            GameObjectType::Consumable(_)
+            | GameObjectType::Teleport(_)
+            | GameObjectType::Experience(_) => ItemCategory::Potion,
+            GameObjectType::Container(_) => ItemCategory::Container,
+        }
+    }
+
+    pub(crate) fn container(&self) -> Option<&Container> {
+        match &self.kind {
+            GameObjectType::Container(container) => Some(container),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn container_mut(&mut self) -> Option<&mut Container> {
+        match &mut self.kind {
+            GameObjectType::Container(container) => Some(container),
+            _ => None,
+        }
+    }
+
+    /// The container called `name`: this item, or one inside it. Nothing inside a locked
+    /// container can be reached.
+    pub(crate) fn find_container(&self, name: &str) -> Option<&Container> {
+        if self.name() == name {
+            return self.container();
+        }
+        let container = self.container().filter(|found| found.lock().is_none())?;
+        container
+            .contents
+            .iter()
+            .find_map(|item| item.find_container(name))
+    }
+
+    pub(crate) fn find_container_mut(&mut self, name: &str) -> Option<&mut Container> {
+        if self.name() == name {
+            return self.container_mut();
+        }
+        let container = self
+            .container_mut()
+            .filter(|found| found.lock().is_none())?;
+        container
+            .contents
+            .iter_mut()
+            .find_map(|item| item.find_container_mut(name))
+    }
+
+    /// The names of this item and the items inside it that are containers, see
+    /// `find_container`.
+    pub(crate) fn container_names(&self) -> Vec<String> {
+        let Some(container) = self.container() else {
+            return Vec::new();
+        };
+        let mut names = vec![self.name().to_string()];
+        if container.lock().is_none() {
+            names.extend(container.contents.iter().flat_map(Item::container_names));
+        }
+        names
+    }
+
+    pub(crate) fn do_action(&self, rng: &mut GameRng) -> u32 {
+        self.kind.do_action(rng)
     }
 
-    pub(crate) fn show(&self) {
-        console_output!(
-            "Item Details:
-Name: {}
-Description: {}
-Details: ",
+    pub(crate) fn show(&self, console: &Console) {
+        console_message!(
+            console,
+            ItemDetails,
             self.base.name,
-            self.base.description
+            self.base.description,
+            self.weight()
         );
-        self.kind.show();
+        self.kind.show(console);
+    }
+}
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.base.name)
+    }
+}
+
+impl GameObjectType {
+    /// A short summary in `language`, e.g. "damage 2-5" or "heals 9".
+    pub(crate) fn summary(&self, language: Language) -> String {
+        match self {
+            GameObjectType::Coin(Coin { value }) => message!(language, CoinSummary, value),
+            GameObjectType::Weapon(Weapon {
+                minimum_damage,
+                maximum_damage,
+            }) => message!(language, WeaponSummary, minimum_damage, maximum_damage),
+            GameObjectType::Armor(Armor { defense }) => {
+                message!(language, ArmorSummary, defense)
+            }
+            GameObjectType::Consumable(Consumable { heal_amount }) => {
+                message!(language, HealSummary, heal_amount)
+            }
+            GameObjectType::Teleport(Teleport {
+                minimum_locations,
+                maximum_locations,
+            }) => message!(
+                language,
+                TeleportSummary,
+                minimum_locations,
+                maximum_locations
+            ),
+            GameObjectType::Experience(Experience {
+                attack_chance_bonus,
+            }) => message!(language, ExperienceSummary, attack_chance_bonus),
+            GameObjectType::Container(Container { lock: Some(_), .. }) => {
+                message!(language, Locked)
+            }
+            GameObjectType::Container(Container { contents, .. }) => {
+                message!(language, ContainerSummary, contents.len())
+            }
+        }
     }
 }
+
 pub(crate) trait Actionable {
-    fn do_action(&self) -> u32;
-    fn show(&self);
+    fn do_action(&self, rng: &mut GameRng) -> u32;
+    fn show(&self, console: &Console);
 }
 
 impl Actionable for GameObjectType {
-    fn do_action(&self) -> u32 {
+    fn do_action(&self, rng: &mut GameRng) -> u32 {
         match self {
             GameObjectType::Coin(Coin { value }) => *value,
             GameObjectType::Weapon(Weapon {
                 minimum_damage,
                 maximum_damage,
-            }) => {
-                let mut rng = rand::thread_rng();
-                rng.gen_range(*minimum_damage..=*maximum_damage)
-            }
+            }) => rng.gen_range(*minimum_damage..=*maximum_damage),
             GameObjectType::Armor(Armor { defense }) => *defense,
             GameObjectType::Consumable(Consumable { heal_amount }) => *heal_amount,
+            GameObjectType::Teleport(Teleport {
+                minimum_locations,
+                maximum_locations,
+            }) => rng.gen_range(*minimum_locations..=*maximum_locations),
+            GameObjectType::Experience(Experience {
+                attack_chance_bonus,
+            }) => *attack_chance_bonus,
+            GameObjectType::Container(Container { contents, .. }) => contents.len() as u32,
         }
     }
-    fn show(&self) {
+    fn show(&self, console: &Console) {
         match self {
             GameObjectType::Coin(Coin { value }) => {
-                console_output!("pouch has amount {}\n", value);
+                console_message!(console, CoinDetails, value);
             }
             GameObjectType::Weapon(Weapon {
                 minimum_damage,
                 maximum_damage,
             }) => {
-                console_output!(
-                    "Weapon has damage range {}-{}\n",
-                    minimum_damage,
-                    maximum_damage
-                );
+                console_message!(console, WeaponDetails, minimum_damage, maximum_damage);
             }
             GameObjectType::Armor(Armor { defense }) => {
-                console_output!("Armor has defense of {}\n", defense);
+                console_message!(console, ArmorDetails, defense);
             }
             GameObjectType::Consumable(Consumable { heal_amount }) => {
-                console_output!("Potion has heals {}\n", heal_amount);
+                console_message!(console, HealDetails, heal_amount);
+            }
+            GameObjectType::Teleport(Teleport {
+                minimum_locations,
+                maximum_locations,
+            }) => {
+                console_message!(
+                    console,
+                    TeleportDetails,
+                    minimum_locations,
+                    maximum_locations
+                );
+            }
+            GameObjectType::Experience(Experience {
+                attack_chance_bonus,
+            }) => {
+                console_message!(console, ExperienceDetails, attack_chance_bonus);
+            }
+            GameObjectType::Container(Container { lock: Some(_), .. }) => {
+                console_message!(console, ContainerLockedDetails);
+            }
+            GameObjectType::Container(Container { contents, .. }) => {
+                console_message!(
+                    console,
+                    ContainerDetails,
+                    item_list(console.language(), contents)
+                );
             }
         }
     }
@@ -115,59 +431,81 @@ impl Actionable for GameObjectType {
 
 pub(crate) struct ItemFactory;
 impl ItemFactory {
+    /// Every `type` in the `Objecten` table must be one of these.
+    pub(crate) const OBJECT_TYPES: [&'static str; 6] = [
+        "teleportatiedrank",
+        "ervaringsdrank",
+        "levenselixer",
+        "wapenrusting",
+        "wapen",
+        "goudstukken",
+    ];
+
+    pub(crate) fn is_known_type(obj_type: &str) -> bool {
+        Self::OBJECT_TYPES.contains(&obj_type)
+    }
+
     pub(crate) fn create_item(
         name: &str,
         description: &str,
         obj_type: &str,
         value: (u32, u32, u32),
+        rng: &mut GameRng,
     ) -> Item {
         let base = GameObject {
             name: name.to_string(),
             description: description.to_string(),
         };
 
-        let mut rng = rand::thread_rng();
+        let kind = match obj_type {
+            "teleportatiedrank" => GameObjectType::Teleport(Teleport {
+                minimum_locations: value.0,
+                maximum_locations: value.1,
+            }),
+            "ervaringsdrank" => GameObjectType::Experience(Experience {
+                attack_chance_bonus: rng.gen_range(value.0..=value.1),
+            }),
+            "levenselixer" => GameObjectType::Consumable(Consumable {
+                heal_amount: rng.gen_range(value.0..=value.1),
+            }),
+            "wapenrusting" => GameObjectType::Armor(Armor { defense: value.2 }),
+            "wapen" => GameObjectType::Weapon(Weapon {
+                minimum_damage: value.0,
+                maximum_damage: value.1,
+            }),
+            "goudstukken" => GameObjectType::Coin(Coin {
+                value: rng.gen_range(value.0..=value.1),
+            }),
+            // Story::new rejects databases with other types when loading.
+            _ => unreachable!("unknown object type '{}'", obj_type),
+        };
+        Item {
+            base,
+            kind,
+            weight: 0,
+            stack: Vec::new(),
+        }
+    }
 
-        match obj_type {
-            "teleportatiedrank" => Item {
-                base,
-                kind: GameObjectType::Consumable(Consumable {
-                    heal_amount: rng.gen_range(value.0..=value.1),
-                }),
-            },
-            "ervaringsdrink" => Item {
-                base,
-                kind: GameObjectType::Consumable(Consumable {
-                    heal_amount: rng.gen_range(value.0..=value.1),
-                }),
-            },
-            "levenselixer" => Item {
-                base,
-                kind: GameObjectType::Consumable(Consumable {
-                    heal_amount: rng.gen_range(value.0..=value.1),
-                }),
-            },
-            "wapenrusting" => Item {
-                base,
-                kind: GameObjectType::Armor(Armor { defense: value.2 }),
-            },
-            "wapen" => Item {
-                base,
-                kind: GameObjectType::Weapon(Weapon {
-                    minimum_damage: value.0,
-                    maximum_damage: value.1,
-                }),
-            },
-            "goudstukken" => Item {
-                base,
-                kind: GameObjectType::Coin(Coin {
-                    value: rng.gen_range(value.0..=value.1),
-                }),
-            },
-            _ => Item {
-                base,
-                kind: GameObjectType::Coin(Coin { value: value.0 }),
+    pub(crate) fn create_container(
+        name: &str,
+        description: &str,
+        contents: Vec<Item>,
+        lock: Option<String>,
+        fixed: bool,
+    ) -> Item {
+        Item {
+            base: GameObject {
+                name: name.to_string(),
+                description: description.to_string(),
             },
+            kind: GameObjectType::Container(Container {
+                contents: stacked(&contents),
+                lock,
+                fixed,
+            }),
+            weight: 0,
+            stack: Vec::new(),
         }
     }
 }
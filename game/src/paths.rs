@@ -0,0 +1,114 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+pub(crate) const STORY_ENV: &str = "KERKERS_STORY";
+pub(crate) const DATABASE_ENV: &str = "KERKERS_DATABASE";
+pub(crate) const SAVE_DIRECTORY_ENV: &str = "KERKERS_SAVES";
+
+const ASSET_DIRECTORY: &str = "assets";
+const SAVE_DIRECTORY: &str = "saves";
+const STORY_FILE: &str = "kasteelruine.xml";
+const DATABASE_FILE: &str = "kerkersendraken.db";
+
+/// Where the game reads its story and database from and writes its saves to.
+#[derive(Clone, Debug)]
+pub(crate) struct GamePaths {
+    pub(crate) story: PathBuf,
+    pub(crate) database: PathBuf,
+    pub(crate) save_directory: PathBuf,
+}
+
+impl GamePaths {
+    /// Each path is taken from the explicit value, then its environment variable, and
+    /// otherwise searched for, see `asset_directories`.
+    pub(crate) fn resolve(
+        story: Option<&Path>,
+        database: Option<&Path>,
+        save_directory: Option<&Path>,
+    ) -> Self {
+        let story = explicit_or_env(story, STORY_ENV)
+            .or_else(|| find_asset(&asset_directories(), STORY_FILE))
+            .unwrap_or_else(|| Path::new(ASSET_DIRECTORY).join(STORY_FILE));
+
+        // A story usually ships with its own database, so look next to it first.
+        let mut database_directories = asset_directories();
+        if let Some(story_directory) = story.parent() {
+            database_directories.insert(0, story_directory.to_path_buf());
+        }
+        let database = explicit_or_env(database, DATABASE_ENV)
+            .or_else(|| find_asset(&database_directories, DATABASE_FILE))
+            .unwrap_or_else(|| Path::new(ASSET_DIRECTORY).join(DATABASE_FILE));
+
+        let save_directory = explicit_or_env(save_directory, SAVE_DIRECTORY_ENV)
+            .unwrap_or_else(|| default_save_directory(&story));
+
+        Self {
+            story,
+            database,
+            save_directory,
+        }
+    }
+}
+
+fn explicit_or_env(explicit: Option<&Path>, variable: &str) -> Option<PathBuf> {
+    explicit
+        .map(Path::to_path_buf)
+        .or_else(|| env::var_os(variable).map(PathBuf::from))
+}
+
+/// `assets` in the working directory and its parent, then next to the executable and up to
+/// three levels above it, which covers both an installed game and `target/<profile>/`.
+fn asset_directories() -> Vec<PathBuf> {
+    let mut directories = vec![
+        PathBuf::from(ASSET_DIRECTORY),
+        Path::new("..").join(ASSET_DIRECTORY),
+    ];
+    if let Ok(executable) = env::current_exe() {
+        for ancestor in executable.ancestors().skip(1).take(4) {
+            directories.push(ancestor.join(ASSET_DIRECTORY));
+        }
+    }
+    directories
+}
+
+fn find_asset(directories: &[PathBuf], file: &str) -> Option<PathBuf> {
+    directories
+        .iter()
+        .map(|directory| directory.join(file))
+        .find(|path| path.is_file())
+}
+
+/// Saves live in a `saves` directory next to the directory holding the story.
+fn default_save_directory(story: &Path) -> PathBuf {
+    let story_directory = story.parent().unwrap_or(Path::new(""));
+    match story_directory.parent() {
+        Some(root) => root.join(SAVE_DIRECTORY),
+        None => PathBuf::from(SAVE_DIRECTORY),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_paths_win() {
+        let paths = GamePaths::resolve(
+            Some(Path::new("adventures/grot.xml")),
+            Some(Path::new("adventures/grot.db")),
+            Some(Path::new("grot_saves")),
+        );
+        assert_eq!(paths.story, Path::new("adventures/grot.xml"));
+        assert_eq!(paths.database, Path::new("adventures/grot.db"));
+        assert_eq!(paths.save_directory, Path::new("grot_saves"));
+    }
+
+    #[test]
+    fn test_default_assets_are_found() {
+        let paths = GamePaths::resolve(None, None, None);
+        assert!(paths.story.is_file());
+        assert!(paths.database.is_file());
+    }
+}
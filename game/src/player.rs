@@ -1,9 +1,20 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::console_output;
-use crate::objects::{GameObjectType, Item};
+use crate::console::console::{Console, Style};
+use crate::objects::{stack_into, take_units, GameObjectType, Item};
+use crate::presentation::item_list;
+use crate::world::GameRng;
+use crate::{console_message, console_output, message};
 
-#[derive(Debug)]
+/// The total weight a new player can carry, weapon and armor included.
+pub(crate) const CARRY_CAPACITY: u32 = 25;
+/// A player carrying more than this percentage of their capacity is encumbered.
+const ENCUMBRANCE_PERCENTAGE: u32 = 80;
+/// Percentage points of attack chance an encumbered player loses.
+const ENCUMBRANCE_PENALTY: u32 = 15;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct Player {
     name: String,
     hp: u32,
@@ -13,45 +24,7 @@ pub(crate) struct Player {
     armor: Option<Item>,
     backpack: Vec<Item>,
     godmode: bool,
-}
-
-impl std::fmt::Display for Player {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "Player Information:
---------------------
-Name:           {}
-HP:             {}
-Gold:           {}
-Attack Chance:  {}%
-Weapon:         {}
-Armor:          {}
-Backpack:       {}
---------------------\n",
-            self.name,
-            self.hp,
-            self.gold,
-            self.attack_chance,
-            match &self.weapon {
-                Some(weapon) => format!("{:?}", weapon),
-                None => "None".to_string(),
-            },
-            match &self.armor {
-                Some(armor) => format!("{:?}", armor),
-                None => "None".to_string(),
-            },
-            if self.backpack.is_empty() {
-                "Empty".to_string()
-            } else {
-                self.backpack
-                    .iter()
-                    .map(|item| format!("{:?}", item))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            }
-        )
-    }
+    capacity: u32,
 }
 
 impl Player {
@@ -65,118 +38,240 @@ impl Player {
             armor: None,
             backpack: Vec::new(),
             godmode: false,
+            capacity: CARRY_CAPACITY,
+        }
+    }
+
+    /// The attack chance with the encumbrance penalty taken off.
+    pub(crate) fn attack_chance(&self) -> u32 {
+        if self.is_encumbered() {
+            self.attack_chance.saturating_sub(ENCUMBRANCE_PENALTY)
+        } else {
+            self.attack_chance
+        }
+    }
+
+    /// The weight of the weapon, the armor and everything in the backpack.
+    pub(crate) fn carried_weight(&self) -> u32 {
+        self.carried_items().iter().map(|item| item.weight()).sum()
+    }
+
+    pub(crate) fn is_encumbered(&self) -> bool {
+        self.carried_weight() * 100 > self.capacity * ENCUMBRANCE_PERCENTAGE
+    }
+
+    /// Whether `item` fits within the capacity on top of what the player carries, tells the
+    /// player when it does not. Coins always fit, they are added to the gold.
+    pub(crate) fn can_carry(&self, console: &Console, item: &Item, count: Option<u32>) -> bool {
+        let carried = self.carried_weight();
+        if matches!(item.kind(), GameObjectType::Coin(_))
+            || carried + item.units_weight(count) <= self.capacity
+        {
+            return true;
         }
+        console_output!(
+            console,
+            "{}",
+            console.styled(
+                Style::Warning,
+                message!(console, TooHeavy, item.name(), carried, self.capacity)
+            )
+        );
+        false
     }
 
     pub(crate) fn toggle_godmode(&mut self) {
         self.godmode = !self.godmode;
     }
 
-    pub(crate) fn show(&self) {
-        console_output!("{self}");
+    pub(crate) fn show(&self, console: &Console) {
+        let language = console.language();
+        let equipment = |item: &Option<Item>| match item {
+            Some(item) => format!("{} ({})", item, item.kind().summary(language)),
+            None => message!(language, NoEquipment),
+        };
+        console_message!(
+            console,
+            PlayerDetails,
+            self.name,
+            self.hp,
+            console.styled(Style::Gold, self.gold),
+            self.attack_chance(),
+            if self.is_encumbered() {
+                message!(
+                    language,
+                    EncumberedWeight,
+                    self.carried_weight(),
+                    self.capacity
+                )
+            } else {
+                message!(
+                    language,
+                    CarriedWeight,
+                    self.carried_weight(),
+                    self.capacity
+                )
+            },
+            equipment(&self.weapon),
+            equipment(&self.armor),
+            if self.backpack.is_empty() {
+                message!(language, EmptyBackpack)
+            } else {
+                item_list(language, &self.backpack)
+            }
+        );
     }
 
-    fn get_weapon_dmg(&self) -> u32 {
+    fn get_weapon_dmg(&self, console: &Console, rng: &mut GameRng) -> u32 {
         if let Some(weapon) = &self.weapon {
-            let damage = weapon.do_action();
-            console_output!("player does total damage {} \n", damage);
+            let damage = weapon.do_action(rng);
+            console_message!(console, PlayerDamage, console.styled(Style::Damage, damage));
             return damage;
         }
-        console_output!("player has no weapon does 0 damage \n");
+        console_message!(console, PlayerUnarmed);
         0
     }
 
-    pub(crate) fn hit(&self) -> u32 {
+    pub(crate) fn hit(&self, console: &Console, rng: &mut GameRng) -> u32 {
         if self.godmode {
-            self.get_weapon_dmg()
+            self.get_weapon_dmg(console, rng)
         } else {
-            let mut rng = thread_rng();
-
-            let real_attack_chance = self.attack_chance as f64 / 100.0;
+            let real_attack_chance = self.attack_chance() as f64 / 100.0;
             if rng.gen_bool(real_attack_chance) {
-                return self.get_weapon_dmg();
+                return self.get_weapon_dmg(console, rng);
             }
-            console_output!("player misses\n");
+            console_message!(console, PlayerMisses);
             0
         }
     }
 
-    pub(crate) fn take_dmg(&mut self, damage: u32) {
+    pub(crate) fn take_dmg(&mut self, console: &Console, damage: u32, rng: &mut GameRng) {
         if self.godmode {
-            console_output!("Player is in godmode. it cannot take damage in this state\n");
+            console_message!(console, PlayerInGodmode);
         } else {
             if damage != 0 {
                 if let Some(armor) = &self.armor {
-                    let defense = armor.do_action();
-                    let actual_damage = if damage > defense {
-                        damage - defense
-                    } else {
-                        0
-                    };
+                    let defense = armor.do_action(rng);
+                    let actual_damage = damage.saturating_sub(defense);
 
-                    console_output!(
-                        "You took {} damage after armor mitigation. Your current HP: {}\n",
-                        actual_damage,
+                    console_message!(
+                        console,
+                        DamageAfterArmor,
+                        console.styled(Style::Damage, actual_damage),
                         self.hp
                     );
 
                     self.hp = self.hp.saturating_sub(actual_damage);
                 } else {
                     self.hp = self.hp.saturating_sub(damage);
-                    console_output!("You took {} damage. Your current HP: {}\n", damage, self.hp);
+                    console_message!(
+                        console,
+                        DamageTaken,
+                        console.styled(Style::Damage, damage),
+                        self.hp
+                    );
                 }
             }
         }
     }
 
-    pub(crate) fn fill_backpack(&mut self, item: Item) {
+    pub(crate) fn fill_backpack(&mut self, console: &Console, item: Item, rng: &mut GameRng) {
         match &item.kind() {
             GameObjectType::Coin(_coin) => {
-                let amount = item.do_action();
+                let amount = item.do_action(rng);
                 self.gold += amount;
-                console_output!("Coin pickup {} player has now {} \n", amount, self.gold,);
+                console_message!(
+                    console,
+                    CoinPickup,
+                    console.styled(Style::Gold, amount),
+                    console.styled(Style::Gold, self.gold),
+                );
+            }
+            _ => {
+                let was_encumbered = self.is_encumbered();
+                stack_into(&mut self.backpack, item);
+                if !was_encumbered && self.is_encumbered() {
+                    console_output!(
+                        console,
+                        "{}",
+                        console.styled(
+                            Style::Warning,
+                            message!(console, Encumbered, ENCUMBRANCE_PENALTY)
+                        )
+                    );
+                }
             }
-            _ => self.backpack.push(item),
         }
     }
 
-    pub(crate) fn remove_item(&mut self, item_name: &str) -> Option<Item> {
+    pub(crate) fn backpack_item_names(&self) -> Vec<String> {
+        self.backpack
+            .iter()
+            .map(|item| item.name().to_string())
+            .collect()
+    }
+
+    pub(crate) fn carried_item_names(&self) -> Vec<String> {
+        self.carried_items()
+            .iter()
+            .map(|item| item.name().to_string())
+            .collect()
+    }
+
+    pub(crate) fn backpack_items(&self) -> &[Item] {
+        &self.backpack
+    }
+
+    pub(crate) fn backpack_items_mut(&mut self) -> &mut [Item] {
+        &mut self.backpack
+    }
+
+    /// The weapon, the armor and everything in the backpack.
+    pub(crate) fn carried_items(&self) -> Vec<&Item> {
+        self.weapon
+            .iter()
+            .chain(&self.armor)
+            .chain(&self.backpack)
+            .collect()
+    }
+
+    /// Takes the item off the player, or only `count` of it when it is a bigger stack in the
+    /// backpack.
+    pub(crate) fn remove_item(
+        &mut self,
+        console: &Console,
+        item_name: &str,
+        count: Option<u32>,
+    ) -> Option<Item> {
         if let Some(weapon) = &self.weapon {
             if weapon.name() == item_name {
                 let removed_item = self.weapon.take();
-                console_output!("Weapon '{}' has been removed from your hand.\n", item_name);
+                console_message!(console, WeaponRemoved, item_name);
                 return removed_item;
             }
         }
         if let Some(armor) = &self.armor {
             if armor.name() == item_name {
                 let removed_item = self.armor.take();
-                console_output!("Armor '{}' has been removed from your body.\n", item_name);
+                console_message!(console, ArmorRemoved, item_name);
                 return removed_item;
             }
         }
 
-        if let Some(position) = self
-            .backpack
-            .iter()
-            .position(|item| item.name() == item_name)
-        {
-            let removed_item = self.backpack.remove(position);
-            console_output!(
-                "Item '{}' has been removed from your backpack.\n",
-                item_name
-            );
+        if let Some(removed_item) = take_units(&mut self.backpack, item_name, count) {
+            console_message!(console, ItemRemoved, removed_item.label());
             return Some(removed_item);
         }
 
         console_output!(
-            "Item '{}' not found in weapon, armor, or backpack.\n",
-            item_name
+            console,
+            "{}",
+            console.styled(Style::Warning, message!(console, ItemNotCarried, item_name))
         );
         None
     }
 
-    pub(crate) fn equip_item(&mut self, item_name: &str) {
+    pub(crate) fn equip_item(&mut self, console: &Console, item_name: &str) {
         if let Some(position) = self
             .backpack
             .iter()
@@ -188,128 +283,292 @@ impl Player {
                 GameObjectType::Weapon(_weapon) => {
                     if let Some(current_weapon) = self.weapon.take() {
                         self.backpack.push(current_weapon);
-                        console_output!("Switched out weapon.\n");
+                        console_message!(console, WeaponSwitched);
                     }
-                    console_output!("Equipping new weapon.\n");
-                    item.show();
+                    console_message!(console, WeaponEquipped);
+                    item.show(console);
                     self.weapon = Some(item);
                 }
                 GameObjectType::Armor(_armor) => {
                     if let Some(current_armor) = self.armor.take() {
                         self.backpack.push(current_armor);
-                        console_output!("Switched out armor.\n");
+                        console_message!(console, ArmorSwitched);
                     }
-                    console_output!("Equipped new armor \n");
-                    item.show();
+                    console_message!(console, ArmorEquipped);
+                    item.show(console);
                     self.armor = Some(item);
                 }
                 _ => {
-                    console_output!("Item '{}' is not equippable.\n", item_name);
+                    console_output!(
+                        console,
+                        "{}",
+                        console.styled(Style::Warning, message!(console, NotEquippable, item_name))
+                    );
                     self.backpack.push(item);
                 }
             }
         } else {
-            console_output!("Item '{}' not found in backpack.\n", item_name);
+            console_output!(
+                console,
+                "{}",
+                console.styled(Style::Warning, message!(console, NotInBackpack, item_name))
+            );
         }
     }
 
-    pub(crate) fn consume_potion(&mut self, item_name: &str) {
-        if let Some(position) = self
-            .backpack
-            .iter()
-            .position(|item| item.name() == item_name)
-        {
-            let item = self.backpack.remove(position);
-
+    /// Returns how many locations the player should be teleported when the potion was a
+    /// teleportation potion, moving the player is up to the world. Only one potion of a
+    /// stack is consumed.
+    pub(crate) fn consume_potion(
+        &mut self,
+        console: &Console,
+        item_name: &str,
+        rng: &mut GameRng,
+    ) -> Option<u32> {
+        if let Some(item) = take_units(&mut self.backpack, item_name, Some(1)) {
             match item.kind() {
                 GameObjectType::Consumable(_consumable) => {
-                    self.hp += item.do_action();
-                    console_output!("Potion is consumed:\n");
-                    item.show();
-                    console_output!("New health: {} \n", self.hp,);
+                    self.hp += item.do_action(rng);
+                    console_message!(console, PotionConsumed);
+                    item.show(console);
+                    console_message!(console, NewHealth, console.styled(Style::Healing, self.hp));
+                }
+                GameObjectType::Experience(_experience) => {
+                    self.attack_chance = (self.attack_chance + item.do_action(rng)).min(100);
+                    console_message!(console, PotionConsumed);
+                    item.show(console);
+                    console_message!(console, NewAttackChance, self.attack_chance);
+                }
+                GameObjectType::Teleport(_teleport) => {
+                    let locations = item.do_action(rng);
+                    console_message!(console, PotionConsumed);
+                    item.show(console);
+                    return Some(locations);
                 }
                 _ => {
-                    console_output!("Item '{}' is not equippable.\n", item_name);
-                    self.backpack.push(item);
+                    console_output!(
+                        console,
+                        "{}",
+                        console.styled(Style::Warning, message!(console, NotEquippable, item_name))
+                    );
+                    stack_into(&mut self.backpack, item);
                 }
             }
         } else {
-            console_output!("Item '{}' not found in backpack.\n", item_name);
+            console_output!(
+                console,
+                "{}",
+                console.styled(Style::Warning, message!(console, NotInBackpack, item_name))
+            );
         }
+        None
     }
     pub(crate) fn hp(&self) -> u32 {
         self.hp
     }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn gold(&self) -> u32 {
+        self.gold
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::console::console::Console;
     use crate::objects::ItemFactory;
+    use rand::SeedableRng;
 
     #[test]
     fn test_player_creation() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
         let player = Player::new("NAME", &weapon);
         assert_eq!(player.hp(), 10);
     }
 
     #[test]
     fn test_take_damage() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
         let mut player = Player::new("NAME", &weapon);
-        player.take_dmg(5);
+        player.take_dmg(&console, 5, &mut rng);
         assert_eq!(player.hp(), 5);
     }
 
     #[test]
     fn test_godmode_prevents_damage() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
         let mut player = Player::new("NAME", &weapon);
         player.toggle_godmode();
-        player.take_dmg(100);
+        player.take_dmg(&console, 100, &mut rng);
         assert_eq!(player.hp(), 10);
     }
 
     #[test]
     fn test_fill_backpack() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
-        let potion = ItemFactory::create_item("HEALTH_POTION", "Potion", "levenselixer", (5, 5, 0));
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
+        let potion = ItemFactory::create_item(
+            "HEALTH_POTION",
+            "Potion",
+            "levenselixer",
+            (5, 5, 0),
+            &mut rng,
+        );
         let mut player = Player::new("NAME", &weapon);
-        player.fill_backpack(potion);
+        player.fill_backpack(&console, potion, &mut rng);
         assert_eq!(player.backpack.len(), 1);
     }
 
     #[test]
     fn test_remove_item() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
         let mut player = Player::new("NAME", &weapon);
-        let armor = ItemFactory::create_item("ARMOR", "Shield", "wapenrusting", (0, 5, 0));
-        player.fill_backpack(armor.clone());
-        let removed_item = player.remove_item("ARMOR");
+        let armor =
+            ItemFactory::create_item("ARMOR", "Shield", "wapenrusting", (0, 5, 0), &mut rng);
+        player.fill_backpack(&console, armor.clone(), &mut rng);
+        let removed_item = player.remove_item(&console, "ARMOR", None);
         assert!(removed_item.is_some());
         assert_eq!(player.backpack.len(), 0);
     }
 
+    #[test]
+    fn test_stacked_potions_keep_their_rolls() {
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
+        let mut player = Player::new("NAME", &weapon);
+        for (name, heal) in [("elixer", 3), ("elixer2", 5), ("elixer3", 7)] {
+            let potion =
+                ItemFactory::create_item(name, "Potion", "levenselixer", (heal, heal, 0), &mut rng)
+                    .with_weight(1);
+            player.fill_backpack(&console, potion, &mut rng);
+        }
+        assert_eq!(player.backpack.len(), 1);
+        assert_eq!(player.backpack[0].label(), "3 x elixer");
+        assert_eq!(player.carried_weight(), 3);
+
+        player.consume_potion(&console, "elixer", &mut rng);
+        assert_eq!(player.hp(), 17);
+        let removed = player.remove_item(&console, "elixer", Some(1)).unwrap();
+        assert_eq!(removed.label(), "elixer");
+        player.consume_potion(&console, "elixer", &mut rng);
+        assert_eq!(player.hp(), 20);
+        assert!(player.backpack.is_empty());
+
+        player.fill_backpack(&console, removed, &mut rng);
+        player.consume_potion(&console, "elixer", &mut rng);
+        assert_eq!(player.hp(), 25);
+    }
+
     #[test]
     fn test_equip_item() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
         let mut player = Player::new("NAME", &weapon);
-        let new_weapon = ItemFactory::create_item("AXE", "AXE", "wapen", (15, 15, 15));
-        player.fill_backpack(new_weapon.clone());
-        player.equip_item("AXE");
+        let new_weapon = ItemFactory::create_item("AXE", "AXE", "wapen", (15, 15, 15), &mut rng);
+        player.fill_backpack(&console, new_weapon.clone(), &mut rng);
+        player.equip_item(&console, "AXE");
         assert!(player.weapon.is_some());
         assert_eq!(player.weapon.unwrap().name(), "AXE");
     }
 
+    #[test]
+    fn test_consume_teleport_potion() {
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
+        let potion = ItemFactory::create_item(
+            "TELEPORT_POTION",
+            "Potion",
+            "teleportatiedrank",
+            (1, 3, 0),
+            &mut rng,
+        );
+        let mut player = Player::new("NAME", &weapon);
+        player.fill_backpack(&console, potion, &mut rng);
+        let locations = player.consume_potion(&console, "TELEPORT_POTION", &mut rng);
+        assert!(matches!(locations, Some(1..=3)));
+        assert_eq!(player.hp(), 10);
+        assert!(player.backpack.is_empty());
+    }
+
+    #[test]
+    fn test_experience_potion_caps_attack_chance() {
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
+        let mut player = Player::new("NAME", &weapon);
+        for name in ["SMALL_XP", "BIG_XP"] {
+            let potion =
+                ItemFactory::create_item(name, "Potion", "ervaringsdrank", (50, 50, 0), &mut rng);
+            player.fill_backpack(&console, potion, &mut rng);
+        }
+        player.consume_potion(&console, "SMALL_XP", &mut rng);
+        assert_eq!(player.attack_chance, 90);
+        player.consume_potion(&console, "BIG_XP", &mut rng);
+        assert_eq!(player.attack_chance, 100);
+    }
+
+    #[test]
+    fn test_capacity_and_encumbrance() {
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng)
+            .with_weight(5);
+        let armor =
+            ItemFactory::create_item("ARMOR", "Shield", "wapenrusting", (0, 5, 0), &mut rng)
+                .with_weight(15);
+        let mut player = Player::new("NAME", &weapon);
+        assert!(player.can_carry(&console, &armor, None));
+        player.fill_backpack(&console, armor, &mut rng);
+        assert_eq!(player.carried_weight(), 20);
+        assert!(!player.is_encumbered());
+        assert_eq!(player.attack_chance(), 40);
+
+        let potion =
+            ItemFactory::create_item("POTION", "Potion", "levenselixer", (5, 5, 0), &mut rng)
+                .with_weight(1);
+        player.fill_backpack(&console, potion, &mut rng);
+        assert!(player.is_encumbered());
+        assert_eq!(player.attack_chance(), 25);
+
+        let axe =
+            ItemFactory::create_item("AXE", "AXE", "wapen", (15, 15, 15), &mut rng).with_weight(5);
+        assert!(!player.can_carry(&console, &axe, None));
+        let coins = ItemFactory::create_item("GOLD", "Gold", "goudstukken", (5, 5, 0), &mut rng)
+            .with_weight(5);
+        assert!(player.can_carry(&console, &coins, None));
+    }
+
     #[test]
     fn test_consume_potion() {
-        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10));
-        let potion = ItemFactory::create_item("HEALTH_POTION", "Potion", "levenselixer", (5, 5, 0));
+        let console = Console::default();
+        let mut rng = GameRng::seed_from_u64(0);
+        let weapon = ItemFactory::create_item("SWORD", "SWORD", "wapen", (10, 10, 10), &mut rng);
+        let potion = ItemFactory::create_item(
+            "HEALTH_POTION",
+            "Potion",
+            "levenselixer",
+            (5, 5, 0),
+            &mut rng,
+        );
         let mut player = Player::new("NAME", &weapon);
-        player.fill_backpack(potion);
-        player.take_dmg(5);
-        player.consume_potion("HEALTH_POTION");
+        player.fill_backpack(&console, potion, &mut rng);
+        player.take_dmg(&console, 5, &mut rng);
+        player.consume_potion(&console, "HEALTH_POTION", &mut rng);
         assert_eq!(player.hp(), 10);
     }
 }
@@ -0,0 +1,84 @@
+//! Turns lists of things in the world into text for the player.
+
+use crate::{message, messages::Language, objects::Item};
+
+/// Joins `parts` the way a sentence would: "a", "a and b", "a, b and c".
+pub(crate) fn prose_list(language: Language, parts: &[String]) -> String {
+    match parts {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => message!(language, ListAnd, rest.join(", "), last),
+    }
+}
+
+/// The items as prose, with items of the same kind and stacks counted together, e.g.
+/// "dolk, langzwaard and 2 x klein levenselixer". Gives "nothing" when there are none.
+pub(crate) fn item_list<'a>(
+    language: Language,
+    items: impl IntoIterator<Item = &'a Item>,
+) -> String {
+    // The first item of each group, to name the group when it has only one item.
+    let mut groups: Vec<(&str, &str, u32)> = Vec::new();
+    for item in items {
+        let name = base_name(item.name());
+        match groups.iter_mut().find(|(group, _, _)| *group == name) {
+            Some((_, _, count)) => *count += item.count(),
+            None => groups.push((name, item.name(), item.count())),
+        }
+    }
+    if groups.is_empty() {
+        return message!(language, Nothing);
+    }
+
+    let parts: Vec<String> = groups
+        .iter()
+        .map(|(name, first, count)| match count {
+            1 => first.to_string(),
+            _ => format!("{} x {}", count, name),
+        })
+        .collect();
+    prose_list(language, &parts)
+}
+
+/// The name without the number `NameGenerator` adds to tell copies apart.
+pub(crate) fn base_name(name: &str) -> &str {
+    name.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{objects::ItemFactory, world::GameRng};
+    use rand::SeedableRng;
+
+    fn potion(name: &str) -> Item {
+        let mut rng = GameRng::seed_from_u64(0);
+        ItemFactory::create_item(name, "Potion", "levenselixer", (1, 10, 0), &mut rng)
+    }
+
+    #[test]
+    fn test_prose_list() {
+        let parts = ["dolk".to_string(), "speer".to_string(), "bijl".to_string()];
+        assert_eq!(prose_list(Language::English, &parts[..1]), "dolk");
+        assert_eq!(prose_list(Language::English, &parts[..2]), "dolk and speer");
+        assert_eq!(
+            prose_list(Language::English, &parts),
+            "dolk, speer and bijl"
+        );
+        assert_eq!(prose_list(Language::Dutch, &parts), "dolk, speer en bijl");
+    }
+
+    #[test]
+    fn test_copies_are_counted_together() {
+        let items = [
+            potion("klein levenselixer"),
+            potion("dolk2"),
+            potion("klein levenselixer2"),
+        ];
+        assert_eq!(
+            item_list(Language::English, &items),
+            "2 x klein levenselixer and dolk2"
+        );
+        assert_eq!(item_list(Language::English, &[]), "nothing");
+    }
+}
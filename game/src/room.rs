@@ -1,28 +1,133 @@
 pub type RoomId = u32;
 
-use crate::{console_output, objects::Item};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+use crate::{
+    console::console::{Console, Style},
+    console_message, console_output, message,
+    messages::{Language, Message},
+    objects::{stack_into, stacked, take_units, Container, Item},
+    presentation::{item_list, prose_list},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Direction {
     North,
     South,
     East,
     West,
+    Up,
+    Down,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
     None,
 }
-#[derive(Clone, Copy, Debug)]
+
+/// What has to be true before the player can take an exit, see `World::exit_blocked`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ExitCondition {
+    /// The player carries an item with this name.
+    Carrying(String),
+    /// No living enemy with this name is in the room.
+    EnemyDead(String),
+    /// The flag is set, see `Room::flag`.
+    Flag(String),
+}
+
+/// A way out of a room, in a direction, by a name such as "put" or "trap", or both.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Exit {
     direction: Direction,
+    name: Option<String>,
     destination: RoomId,
+    /// The name of the item that unlocks the exit, while it is locked.
+    lock: Option<String>,
+    /// Hidden exits cannot be seen or used until the player searches the room.
+    hidden: bool,
+    conditions: Vec<ExitCondition>,
+    /// Shown instead of the usual message while a condition is not met.
+    blocked_message: Option<String>,
 }
 
 impl Exit {
+    pub(crate) fn new(destination: RoomId, direction: Direction, name: Option<String>) -> Self {
+        Self {
+            direction,
+            name,
+            destination,
+            lock: None,
+            hidden: false,
+            conditions: Vec::new(),
+            blocked_message: None,
+        }
+    }
+
+    pub(crate) fn locked_with(mut self, key: String) -> Self {
+        self.lock = Some(key);
+        self
+    }
+
+    pub(crate) fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    pub(crate) fn with_condition(mut self, condition: ExitCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub(crate) fn with_blocked_message(mut self, message: String) -> Self {
+        self.blocked_message = Some(message);
+        self
+    }
+
+    pub(crate) fn lock(&self) -> Option<&str> {
+        self.lock.as_deref()
+    }
+
+    pub(crate) fn conditions(&self) -> &[ExitCondition] {
+        &self.conditions
+    }
+
+    pub(crate) fn blocked_message(&self) -> Option<&str> {
+        self.blocked_message.as_deref()
+    }
+
     pub(crate) fn id(&self) -> RoomId {
         self.destination
     }
+
+    pub(crate) fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Whether the player means this exit with `input`, its direction or its name.
+    fn is_called(&self, input: &str) -> bool {
+        let direction = Direction::from_str(input);
+        (direction != Direction::None && direction == self.direction)
+            || self
+                .name
+                .as_ref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(input.trim()))
+    }
+
+    /// The name of a named exit, otherwise its direction in `language`.
+    pub(crate) fn label(&self, language: Language) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self.direction.name(language).to_string(),
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct Room {
     id: RoomId,
     name: String,
@@ -30,6 +135,8 @@ pub(crate) struct Room {
     exits: Vec<Exit>,
     visible_items: Vec<Item>,
     invisible_items: Vec<Item>,
+    /// Set when the player first enters the room, for exits with an `ExitCondition::Flag`.
+    flag: Option<String>,
 }
 
 impl Room {
@@ -39,46 +146,58 @@ impl Room {
         description: &str,
         vis_items: &[Item],
         invis_items: &[Item],
-        exits_in: &[(u32, String)],
+        exits: Vec<Exit>,
+        flag: Option<String>,
     ) -> Self {
-        let mut exits: Vec<Exit> = Vec::new();
-
-        for exit_input in exits_in.iter() {
-            if exit_input.0 != 0 {
-                exits.push(Exit {
-                    direction: Direction::from_str(exit_input.1.as_str()),
-                    destination: exit_input.0,
-                })
-            }
-        }
-
         Self {
             id,
             name: name.to_string(),
             description: description.to_string(),
             exits,
-            visible_items: vis_items.to_vec(),
-            invisible_items: invis_items.to_vec(),
+            visible_items: stacked(vis_items),
+            invisible_items: stacked(invis_items),
+            flag,
         }
     }
 
     pub(crate) fn append_items(&mut self, items: &mut Vec<Item>) {
-        self.visible_items.append(items);
+        for item in items.drain(..) {
+            stack_into(&mut self.visible_items, item);
+        }
     }
 
-    pub(crate) fn show(&self) {
+    /// Prints the room, naming the destination of each exit from `rooms`.
+    pub(crate) fn show(&self, console: &Console, rooms: &[Room]) {
+        let language = console.language();
         let name = &self.name;
         let des = &self.description;
-        console_output!("{name}\n");
-        console_output!("{des}\n");
-        console_output!("\nVisible items: ");
-        for item in &self.visible_items {
-            console_output!(" {item:?} ");
-        }
+        console_output!(console, "{}\n", console.styled(Style::RoomTitle, name));
+        console_output!(console, "{des}\n");
+        console_message!(console, RoomItems, item_list(language, &self.visible_items));
 
-        console_output!("\nAvailable exits: ");
-        for exit in &self.exits {
-            console_output!(" {exit:?} ");
+        let exits: Vec<String> = self
+            .visible_exits()
+            .map(|exit| {
+                let mut details: Vec<&str> = rooms
+                    .iter()
+                    .find(|room| room.id == exit.destination)
+                    .map(|room| room.name.as_str())
+                    .into_iter()
+                    .collect();
+                if exit.lock.is_some() {
+                    details.push(Message::Locked.text_in(language));
+                }
+                if details.is_empty() {
+                    exit.label(language)
+                } else {
+                    format!("{} ({})", exit.label(language), details.join(", "))
+                }
+            })
+            .collect();
+        if exits.is_empty() {
+            console_message!(console, NoExits);
+        } else {
+            console_message!(console, RoomExits, prose_list(language, &exits));
         }
     }
 
@@ -86,48 +205,100 @@ impl Room {
         self.id
     }
 
+    pub(crate) fn visible_items(&self) -> &[Item] {
+        &self.visible_items
+    }
+
+    pub(crate) fn visible_items_mut(&mut self) -> &mut [Item] {
+        &mut self.visible_items
+    }
+
     pub(crate) fn get_exits(&self) -> &Vec<Exit> {
         &self.exits
     }
 
     pub(crate) fn move_invis_to_visible_items(&mut self) {
-        self.visible_items.append(&mut self.invisible_items);
+        for item in self.invisible_items.drain(..) {
+            stack_into(&mut self.visible_items, item);
+        }
     }
 
-    pub(crate) fn check_direction(&self, new_direction: &Direction) -> u32 {
-        let new_direction = *new_direction;
-        if new_direction == Direction::None {
-            return 0;
+    /// Where the exit the player means with `input` leads, see `Exit::is_called`.
+    /// Hidden exits are left out until the room is searched.
+    pub(crate) fn check_direction(&self, input: &str) -> Option<&Exit> {
+        self.visible_exits().find(|exit| exit.is_called(input))
+    }
+
+    /// The exits the player knows about.
+    pub(crate) fn visible_exits(&self) -> impl Iterator<Item = &Exit> {
+        self.exits.iter().filter(|exit| !exit.hidden)
+    }
+
+    /// The names of the named exits the player knows about, e.g. "put".
+    pub(crate) fn exit_names(&self) -> Vec<String> {
+        self.visible_exits()
+            .filter_map(|exit| exit.name.clone())
+            .collect()
+    }
+
+    /// Makes the hidden exits visible and returns them.
+    pub(crate) fn reveal_hidden_exits(&mut self) -> Vec<Exit> {
+        let mut revealed = Vec::new();
+        for exit in self.exits.iter_mut().filter(|exit| exit.hidden) {
+            exit.hidden = false;
+            revealed.push(exit.clone());
         }
+        revealed
+    }
 
-        let mut is_good_direction = 0;
-        for exit in &self.exits {
-            if exit.direction == new_direction {
-                is_good_direction = exit.destination;
-                break;
+    /// Unlocks the exits to `destination` that `key` fits.
+    pub(crate) fn unlock_exits_to(&mut self, destination: RoomId, key: &str) {
+        for exit in &mut self.exits {
+            if exit.destination == destination && exit.lock.as_deref() == Some(key) {
+                exit.lock = None;
             }
         }
+    }
 
-        is_good_direction
+    pub(crate) fn flag(&self) -> Option<&str> {
+        self.flag.as_deref()
     }
 
     pub(crate) fn insert_item(&mut self, item: Item) {
-        self.visible_items.push(item);
+        stack_into(&mut self.visible_items, item);
     }
 
-    pub(crate) fn move_item(&mut self, item_name: &str) -> Option<Item> {
+    /// Takes the item out of the room, or only `count` of it when it is a bigger stack.
+    pub(crate) fn move_item(
+        &mut self,
+        console: &Console,
+        item_name: &str,
+        count: Option<u32>,
+    ) -> Option<Item> {
         if let Some(position) = self
             .visible_items
             .iter()
             .position(|item| item.name() == item_name)
         {
-            let item = self.visible_items.remove(position);
-            console_output!("Item '{}' has been moved to your backpack.\n", item_name);
+            if self.visible_items[position]
+                .container()
+                .is_some_and(Container::is_fixed)
+            {
+                console_output!(
+                    console,
+                    "{}",
+                    console.styled(Style::Warning, message!(console, ContainerFixed, item_name))
+                );
+                return None;
+            }
+            let item = take_units(&mut self.visible_items, item_name, count)?;
+            console_message!(console, ItemTaken, item.label());
             Some(item)
         } else {
             console_output!(
-                "Item '{}' not found in the room's visible items.\n",
-                item_name
+                console,
+                "{}",
+                console.styled(Style::Warning, message!(console, ItemNotInRoom, item_name))
             );
             None
         }
@@ -135,13 +306,56 @@ impl Room {
 }
 
 impl Direction {
+    /// The name of the direction in `language`, e.g. "north" or "noord".
+    pub(crate) fn name(self, language: Language) -> &'static str {
+        let name = match self {
+            Direction::North => Message::North,
+            Direction::South => Message::South,
+            Direction::East => Message::East,
+            Direction::West => Message::West,
+            Direction::Up => Message::Up,
+            Direction::Down => Message::Down,
+            Direction::NorthEast => Message::NorthEast,
+            Direction::NorthWest => Message::NorthWest,
+            Direction::SouthEast => Message::SouthEast,
+            Direction::SouthWest => Message::SouthWest,
+            Direction::None => Message::Nowhere,
+        };
+        name.text_in(language)
+    }
+
+    /// Accepts English and Dutch names and their first letters in any case,
+    /// e.g. "North", "n", "noord", "Z", "up", "omlaag", "NE" or "zuidwest".
     pub(crate) fn from_str(input: &str) -> Self {
-        match input {
-            "North" => Direction::North,
-            "East" => Direction::East,
-            "West" => Direction::West,
-            "South" => Direction::South,
+        match input.trim().to_lowercase().as_str() {
+            "north" | "n" | "noord" => Direction::North,
+            "east" | "e" | "oost" | "o" => Direction::East,
+            "west" | "w" => Direction::West,
+            "south" | "s" | "zuid" | "z" => Direction::South,
+            "up" | "u" | "omhoog" | "boven" => Direction::Up,
+            "down" | "d" | "omlaag" | "beneden" => Direction::Down,
+            "northeast" | "north-east" | "ne" | "noordoost" | "no" => Direction::NorthEast,
+            "northwest" | "north-west" | "nw" | "noordwest" => Direction::NorthWest,
+            "southeast" | "south-east" | "se" | "zuidoost" | "zo" => Direction::SouthEast,
+            "southwest" | "south-west" | "sw" | "zuidwest" | "zw" => Direction::SouthWest,
             _ => Direction::None,
         }
     }
+
+    /// The direction that leads back, e.g. Down for Up. None stays None.
+    pub(crate) fn opposite(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::NorthEast => Direction::SouthWest,
+            Direction::SouthWest => Direction::NorthEast,
+            Direction::NorthWest => Direction::SouthEast,
+            Direction::SouthEast => Direction::NorthWest,
+            Direction::None => Direction::None,
+        }
+    }
 }
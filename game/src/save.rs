@@ -0,0 +1,371 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    message,
+    messages::Language,
+    player::CARRY_CAPACITY,
+    room::RoomId,
+    world::{GameRng, World},
+};
+
+/// Bump this whenever a serialized struct changes shape and add a step to `migrate`.
+pub(crate) const SAVE_FORMAT_VERSION: u32 = 3;
+const SAVE_EXTENSION: &str = "json";
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u32,
+    world: &'a World,
+}
+
+#[derive(Deserialize)]
+struct LoadedSaveFile {
+    world: World,
+}
+
+#[derive(Debug)]
+pub(crate) enum SaveError {
+    InvalidSlot(String),
+    Io(io::Error),
+    Format(serde_json::Error),
+    MissingVersion,
+    MissingWorld,
+    UnknownLocation(RoomId),
+    UnsupportedVersion(u32),
+}
+
+impl SaveError {
+    /// What went wrong, in `language`.
+    pub(crate) fn describe(&self, language: Language) -> String {
+        match self {
+            SaveError::InvalidSlot(slot) => message!(language, InvalidSlot, slot),
+            SaveError::Io(error) => error.to_string(),
+            SaveError::Format(error) => message!(language, CorruptSave, error),
+            SaveError::MissingVersion => message!(language, SaveWithoutVersion),
+            SaveError::MissingWorld => message!(language, SaveWithoutWorld),
+            SaveError::UnknownLocation(location) => {
+                message!(language, SaveWithUnknownLocation, location)
+            }
+            SaveError::UnsupportedVersion(version) => {
+                message!(
+                    language,
+                    UnsupportedSaveVersion,
+                    version,
+                    SAVE_FORMAT_VERSION
+                )
+            }
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> Self {
+        SaveError::Format(error)
+    }
+}
+
+pub(crate) fn save_world(
+    world: &World,
+    directory: &Path,
+    slot: &str,
+) -> Result<PathBuf, SaveError> {
+    let path = slot_path(directory, slot)?;
+    fs::create_dir_all(directory)?;
+    fs::write(&path, encode(world)?)?;
+    Ok(path)
+}
+
+pub(crate) fn load_world(directory: &Path, slot: &str) -> Result<World, SaveError> {
+    let path = slot_path(directory, slot)?;
+    let content = fs::read_to_string(path)?;
+    decode(&content)
+}
+
+fn slot_path(directory: &Path, slot: &str) -> Result<PathBuf, SaveError> {
+    let is_valid = !slot.is_empty()
+        && slot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !is_valid {
+        return Err(SaveError::InvalidSlot(slot.to_string()));
+    }
+
+    let mut path = directory.join(slot);
+    path.set_extension(SAVE_EXTENSION);
+    Ok(path)
+}
+
+fn encode(world: &World) -> Result<String, SaveError> {
+    let save = SaveFile {
+        version: SAVE_FORMAT_VERSION,
+        world,
+    };
+    Ok(serde_json::to_string_pretty(&save)?)
+}
+
+fn decode(content: &str) -> Result<World, SaveError> {
+    let mut save: Value = serde_json::from_str(content)?;
+    let version = save
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(SaveError::MissingVersion)? as u32;
+
+    if version > SAVE_FORMAT_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+    for from_version in version..SAVE_FORMAT_VERSION {
+        migrate(from_version, &mut save)?;
+    }
+
+    let loaded: LoadedSaveFile = serde_json::from_value(save)?;
+    // A save does not go through `Story::new`, so its locations have to be checked here.
+    match loaded.world.unknown_location() {
+        Some(location) => Err(SaveError::UnknownLocation(location)),
+        None => Ok(loaded.world),
+    }
+}
+
+/// Rewrites a save of `from_version` in place so it matches `from_version + 1`.
+fn migrate(from_version: u32, save: &mut Value) -> Result<(), SaveError> {
+    let world = save
+        .get_mut("world")
+        .and_then(Value::as_object_mut)
+        .ok_or(SaveError::MissingWorld)?;
+
+    match from_version {
+        // Version 1 did not store the random generator, continue from a fresh seed.
+        1 => {
+            let seed: u64 = thread_rng().gen();
+            world.insert("seed".to_string(), Value::from(seed));
+            world.insert(
+                "rng".to_string(),
+                serde_json::to_value(GameRng::seed_from_u64(seed))?,
+            );
+        }
+        // Version 2 had no weights or stacks: items get the weight of their type and are
+        // stacks of one, the player gets the capacity of a new player. Saves made while these
+        // were added without a new version already have them and keep them.
+        2 => {
+            for_each_item(world, &mut |item| {
+                let weight = item
+                    .get("kind")
+                    .and_then(Value::as_object)
+                    .and_then(|kind| kind.keys().next())
+                    .map_or(1, |kind| type_weight(kind));
+                item.entry("weight").or_insert(Value::from(weight));
+                item.entry("stack").or_insert(Value::Array(Vec::new()));
+            });
+            if let Some(player) = world.get_mut("player").and_then(Value::as_object_mut) {
+                player
+                    .entry("capacity")
+                    .or_insert(Value::from(CARRY_CAPACITY));
+            }
+            // Nor did it have flags or exits other than plain open ones.
+            world.entry("flags").or_insert(Value::Array(Vec::new()));
+            let rooms = world.get_mut("rooms").and_then(Value::as_array_mut);
+            for room in rooms.into_iter().flatten().filter_map(Value::as_object_mut) {
+                room.entry("flag").or_insert(Value::Null);
+                let exits = room.get_mut("exits").and_then(Value::as_array_mut);
+                for exit in exits.into_iter().flatten().filter_map(Value::as_object_mut) {
+                    exit.entry("name").or_insert(Value::Null);
+                    exit.entry("lock").or_insert(Value::Null);
+                    exit.entry("hidden").or_insert(Value::Bool(false));
+                    exit.entry("conditions").or_insert(Value::Array(Vec::new()));
+                    exit.entry("blocked_message").or_insert(Value::Null);
+                }
+            }
+        }
+        _ => unreachable!("no migration from save format version {}", from_version),
+    }
+    Ok(())
+}
+
+/// The `gewicht` of the type in the shipped database an item of `kind` is made from, or 1
+/// when the type has none, as for containers.
+fn type_weight(kind: &str) -> u32 {
+    match kind {
+        "Weapon" => 4,
+        "Armor" => 8,
+        "Coin" => 0,
+        _ => 1,
+    }
+}
+
+/// Calls `f` on every item in `value`, in rooms, on enemies, on the player and in containers.
+fn for_each_item(value: &mut Map<String, Value>, f: &mut impl FnMut(&mut Map<String, Value>)) {
+    if value.contains_key("base") && value.contains_key("kind") {
+        f(value);
+    }
+    for child in value.values_mut() {
+        match child {
+            Value::Object(object) => for_each_item(object, f),
+            Value::Array(array) => {
+                for object in array.iter_mut().filter_map(Value::as_object_mut) {
+                    for_each_item(object, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        console::console::{Console, MemoryInput, MemoryOutput},
+        story::Story,
+        test_story::{TempDir, DATABASE},
+        Game, GameOptions,
+    };
+
+    fn shipped_world() -> World {
+        World::new(
+            "Tester",
+            7,
+            &Story::new(Path::new("../assets/kasteelruine.xml"), Path::new(DATABASE)).unwrap(),
+            Console::default(),
+        )
+        .unwrap()
+    }
+
+    /// Plays the shipped story with `seed` and saves in `saves`, returns what the game printed
+    /// from the first hit on.
+    fn play_from_first_hit(seed: u64, saves: &Path, input: &[&str]) -> String {
+        let options = GameOptions {
+            seed: Some(seed),
+            save_directory: Some(saves.to_path_buf()),
+            ..GameOptions::default()
+        };
+        let output = MemoryOutput::new();
+        let input = MemoryInput::new(input.iter().copied());
+        let mut game =
+            Game::with_options(options, Box::new(input), Box::new(output.clone())).unwrap();
+        game.run();
+        let transcript = output.contents();
+        let hit = transcript.find("player does total damage").unwrap();
+        transcript[hit..].to_string()
+    }
+
+    #[test]
+    fn test_saved_world_loads_back_unchanged() {
+        let saves = TempDir::new("round_trip");
+        let mut world = shipped_world();
+        world.player_hit();
+        save_world(&world, saves.path(), "rondje").unwrap();
+        let mut loaded = load_world(saves.path(), "rondje").unwrap();
+
+        // Rooms, enemies, the player, the name generator and the random generator.
+        let as_value = |world: &World| serde_json::to_value(world).unwrap();
+        assert_eq!(as_value(&loaded), as_value(&world));
+        for _ in 0..5 {
+            assert_eq!(loaded.player_hit(), world.player_hit());
+        }
+    }
+
+    #[test]
+    fn test_loaded_game_plays_on_like_the_saved_one() {
+        let saves = TempDir::new("save_and_load");
+        let turns = ["Hit Grote Rat2", "Wait", "Go South", "SeePlayer", "Quit"];
+        let saved: Vec<&str> = ["Tester", "Godmode", "Go North", "Save rondje"]
+            .into_iter()
+            .chain(turns)
+            .collect();
+        let loaded: Vec<&str> = ["Tester", "Load rondje"].into_iter().chain(turns).collect();
+
+        let played = play_from_first_hit(3, saves.path(), &saved);
+        // Another seed, so everything after the Load has to come from the save.
+        let replayed = play_from_first_hit(4, saves.path(), &loaded);
+        assert_eq!(replayed, played);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let content = format!(
+            "{{\"version\": {}, \"world\": {{}}}}",
+            SAVE_FORMAT_VERSION + 1
+        );
+        assert!(matches!(
+            decode(&content),
+            Err(SaveError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_version_is_rejected() {
+        assert!(matches!(
+            decode("{\"world\": {}}"),
+            Err(SaveError::MissingVersion)
+        ));
+    }
+
+    #[test]
+    fn test_version_1_saves_get_a_random_generator() {
+        let mut save: Value = serde_json::from_str("{\"version\": 1, \"world\": {}}").unwrap();
+        migrate(1, &mut save).unwrap();
+        assert!(save["world"]["seed"].is_u64());
+        assert!(save["world"]["rng"].is_object());
+    }
+
+    #[test]
+    fn test_version_2_items_get_the_weight_of_their_type() {
+        let world = decode(include_str!("../tests/saves/version2.json")).unwrap();
+        let player = world.get_player();
+        assert!(!player.carried_items().is_empty());
+        for item in player.carried_items() {
+            assert!(item.weight() > 0, "{} weighs nothing", item.name());
+        }
+        assert_eq!(player.carried_weight(), 5);
+        assert!(player.carried_items().iter().all(|item| item.count() == 1));
+    }
+
+    #[test]
+    fn test_version_2_exits_are_open() {
+        let world = decode(include_str!("../tests/saves/version2.json")).unwrap();
+        let exits = world.get_current_room().get_exits();
+        assert!(!exits.is_empty());
+        assert!(exits.iter().all(|exit| world.exit_blocked(exit).is_none()));
+        assert_eq!(
+            world.get_current_room().visible_exits().count(),
+            exits.len()
+        );
+    }
+
+    #[test]
+    fn test_unknown_locations_are_rejected() {
+        let mut save: Value = serde_json::from_str(&encode(&shipped_world()).unwrap()).unwrap();
+        save["world"]["current_room"] = Value::from(999);
+        assert!(matches!(
+            decode(&save.to_string()),
+            Err(SaveError::UnknownLocation(999))
+        ));
+
+        let mut save: Value = serde_json::from_str(&encode(&shipped_world()).unwrap()).unwrap();
+        save["world"]["enemies"][0]["id"] = Value::from(998);
+        assert!(matches!(
+            decode(&save.to_string()),
+            Err(SaveError::UnknownLocation(998))
+        ));
+    }
+
+    #[test]
+    fn test_slot_names_cannot_escape_save_directory() {
+        let directory = Path::new("saves");
+        assert!(slot_path(directory, "../escape").is_err());
+        assert!(slot_path(directory, "").is_err());
+        assert!(slot_path(directory, "run-1_a").is_ok());
+    }
+}
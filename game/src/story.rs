@@ -1,14 +1,24 @@
-use std::fs;
+use std::{fs, path::Path};
 
 use quick_xml::{
-    events::{attributes::Attribute, Event},
+    events::{attributes::Attribute, BytesStart, Event},
     Reader,
 };
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, Rng};
 
-use crate::{enemy::Enemy, name_generator::NameGenerator, objects::ItemFactory};
+use crate::{enemy::Enemy, name_generator::NameGenerator, objects::ItemFactory, world::GameRng};
 
-use crate::{database::Database, objects::Item, room::Room};
+use crate::{
+    database::Database,
+    error::GameError,
+    messages::Language,
+    objects::Item,
+    room::{Direction, Exit, ExitCondition, Room, RoomId},
+};
+
+const STARTING_WEAPON: &str = "dolk";
+/// Weight of an empty container without a `gewicht`.
+const CONTAINER_WEIGHT: u32 = 1;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Locatie {
@@ -22,6 +32,31 @@ pub(crate) struct Locatie {
     objectenzichtbaar: Option<String>,
     naam: String,
     beschrijving: String,
+    /// Descriptions in other languages, from `<beschrijving taal="en">`.
+    vertalingen: Vec<(Language, String)>,
+    /// Exits other than the four compass directions, from
+    /// `<uitgang naar="15" richting="omlaag" naam="put"/>`.
+    uitgangen: Vec<Exit>,
+    /// Flag set when the player enters, see `ExitCondition::Flag`.
+    vlag: Option<String>,
+    /// Containers in the location, from `<houder naam="kist" inhoud="dolk;speer"/>`.
+    houders: Vec<Houder>,
+}
+
+/// A container and what is in it, which can be other containers.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Houder {
+    naam: String,
+    beschrijving: String,
+    inhoud: Option<String>,
+    /// The item that unlocks the container.
+    slot: Option<String>,
+    /// Fixed containers cannot be taken, like a heavy chest.
+    vast: bool,
+    /// The weight of the empty container.
+    gewicht: u32,
+    verborgen: bool,
+    houders: Vec<Houder>,
 }
 
 pub(crate) struct Story {
@@ -30,10 +65,31 @@ pub(crate) struct Story {
 }
 
 impl Story {
-    pub(crate) fn new(xml_path: &str) -> Self {
-        let db = Database::new("../assets/kerkersendraken.db");
-        let xml_bytes = fs::read(xml_path).expect("Failed to read xml file");
-        let xml_content = String::from_utf8(xml_bytes).unwrap();
+    /// Loads a story that is ready to play, i.e. every exit leads to a location in the story.
+    pub(crate) fn new(xml_path: &Path, database_path: &Path) -> Result<Self, GameError> {
+        let story = Self::load(xml_path, database_path)?;
+        match story.dangling_exits().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(story),
+        }
+    }
+
+    /// Reads the story without checking that the locations fit together, see `check`.
+    pub(crate) fn load(xml_path: &Path, database_path: &Path) -> Result<Self, GameError> {
+        let db = Database::new(database_path)?;
+        for object in db.get_all_objects() {
+            if !ItemFactory::is_known_type(&object.type_obj) {
+                return Err(GameError::UnknownObjectType {
+                    object: object.name.clone(),
+                    object_type: object.type_obj.clone(),
+                });
+            }
+        }
+        let xml_content =
+            fs::read_to_string(xml_path).map_err(|source| GameError::MissingFile {
+                path: xml_path.to_path_buf(),
+                source,
+            })?;
         let mut reader = Reader::from_str(&xml_content);
 
         let mut locaties: Vec<Locatie> = Vec::new();
@@ -48,153 +104,554 @@ impl Story {
             objectenzichtbaar: None,
             naam: String::new(),
             beschrijving: String::new(),
+            vertalingen: Vec::new(),
+            uitgangen: Vec::new(),
+            vlag: None,
+            houders: Vec::new(),
         };
+        let malformed = |location: RoomId, message: String| GameError::MalformedXml {
+            path: xml_path.to_path_buf(),
+            location: (location != 0).then_some(location),
+            message,
+        };
+        // Inside a `beschrijving`, with the language it is in when it has a `taal` attribute.
+        let mut description: Option<Option<Language>> = None;
+        // The `houder` elements that are still open, the innermost last.
+        let mut open_houders: Vec<Houder> = Vec::new();
 
         loop {
             match reader.read_event() {
-                Ok(Event::Start(ref e)) => {
-                    if e.name().0 == b"locatie" {
-                        // Reset the current_locatie
-                        current_locatie = Locatie {
-                            id: 0,
-                            noord: None,
-                            oost: None,
-                            zuid: None,
-                            west: None,
-                            vijand: None,
-                            objectenverborgen: None,
-                            objectenzichtbaar: None,
-                            naam: String::new(),
-                            beschrijving: String::new(),
-                        };
+                Ok(Event::Start(ref e)) if e.name().0 == b"locatie" => {
+                    // Reset the current_locatie
+                    current_locatie = Locatie {
+                        id: 0,
+                        noord: None,
+                        oost: None,
+                        zuid: None,
+                        west: None,
+                        vijand: None,
+                        objectenverborgen: None,
+                        objectenzichtbaar: None,
+                        naam: String::new(),
+                        beschrijving: String::new(),
+                        vertalingen: Vec::new(),
+                        uitgangen: Vec::new(),
+                        vlag: None,
+                        houders: Vec::new(),
+                    };
 
-                        for attr in e.attributes() {
-                            match attr {
-                                Ok(Attribute { key, value }) => {
-                                    let value_str = String::from_utf8_lossy(&value).into_owned();
-                                    match key.0 {
-                                        b"id" => {
-                                            current_locatie.id = value_str.parse().unwrap_or(0)
-                                        }
-                                        b"noord" => {
-                                            current_locatie.noord =
-                                                Some(value_str.parse().unwrap_or(0))
-                                        }
-                                        b"oost" => {
-                                            current_locatie.oost =
-                                                Some(value_str.parse().unwrap_or(0))
-                                        }
-                                        b"zuid" => {
-                                            current_locatie.zuid =
-                                                Some(value_str.parse().unwrap_or(0))
-                                        }
-                                        b"west" => {
-                                            current_locatie.west =
-                                                Some(value_str.parse().unwrap_or(0))
-                                        }
-                                        b"vijand" => current_locatie.vijand = Some(value_str),
-                                        b"objectenverborgen" => {
-                                            current_locatie.objectenverborgen = Some(value_str)
-                                        }
-                                        b"objectenzichtbaar" => {
-                                            current_locatie.objectenzichtbaar = Some(value_str)
-                                        }
-                                        b"naam" => current_locatie.naam = value_str,
-                                        _ => (),
-                                    }
+                    for attr in e.attributes() {
+                        let Attribute { key, value } =
+                            attr.map_err(|error| malformed(current_locatie.id, error.to_string()))?;
+                        let value_str = String::from_utf8_lossy(&value).into_owned();
+                        let parse_id = |value: &str| {
+                            value.parse::<u32>().map_err(|_| {
+                                malformed(
+                                    current_locatie.id,
+                                    format!(
+                                        "attribute '{}' has invalid location id '{}'",
+                                        String::from_utf8_lossy(key.0),
+                                        value
+                                    ),
+                                )
+                            })
+                        };
+                        match key.0 {
+                            b"id" => current_locatie.id = parse_id(&value_str)?,
+                            b"noord" => current_locatie.noord = Some(parse_id(&value_str)?),
+                            b"oost" => current_locatie.oost = Some(parse_id(&value_str)?),
+                            b"zuid" => current_locatie.zuid = Some(parse_id(&value_str)?),
+                            b"west" => current_locatie.west = Some(parse_id(&value_str)?),
+                            b"vijand" => current_locatie.vijand = Some(value_str),
+                            b"objectenverborgen" => {
+                                current_locatie.objectenverborgen = Some(value_str)
+                            }
+                            b"objectenzichtbaar" => {
+                                current_locatie.objectenzichtbaar = Some(value_str)
+                            }
+                            b"naam" => current_locatie.naam = value_str,
+                            b"vlag" => current_locatie.vlag = Some(value_str),
+                            _ => (),
+                        }
+                    }
+                }
+                Ok(Event::Start(ref e) | Event::Empty(ref e)) if e.name().0 == b"uitgang" => {
+                    let location = current_locatie.id;
+                    let (mut target, mut direction, mut name) = (None, Direction::None, None);
+                    let mut settings: Vec<(Vec<u8>, String)> = Vec::new();
+                    for attr in e.attributes() {
+                        let Attribute { key, value } =
+                            attr.map_err(|error| malformed(location, error.to_string()))?;
+                        let value = String::from_utf8_lossy(&value).into_owned();
+                        match key.0 {
+                            b"naar" => {
+                                target = Some(value.parse::<RoomId>().map_err(|_| {
+                                    malformed(
+                                        location,
+                                        format!("uitgang has invalid location id '{}'", value),
+                                    )
+                                })?)
+                            }
+                            b"richting" => {
+                                direction = Direction::from_str(&value);
+                                if direction == Direction::None {
+                                    return Err(malformed(
+                                        location,
+                                        format!("uitgang has unknown direction '{}'", value),
+                                    ));
                                 }
-                                Err(e) => eprintln!("Error reading attribute: {}", e),
                             }
+                            b"naam" => name = Some(value),
+                            _ => settings.push((key.0.to_vec(), value)),
                         }
                     }
+                    let Some(target) = target else {
+                        return Err(malformed(location, "uitgang has no 'naar'".to_string()));
+                    };
+                    if direction == Direction::None && name.is_none() {
+                        return Err(malformed(
+                            location,
+                            "uitgang needs a 'richting', a 'naam' or both".to_string(),
+                        ));
+                    }
+                    let mut exit = Exit::new(target, direction, name);
+                    for (key, value) in settings {
+                        exit = match key.as_slice() {
+                            b"slot" => exit.locked_with(value),
+                            b"verborgen" if ["ja", "true", "1"].contains(&value.as_str()) => {
+                                exit.hidden()
+                            }
+                            b"vereist_voorwerp" => {
+                                exit.with_condition(ExitCondition::Carrying(value))
+                            }
+                            b"bewaakt_door" => exit.with_condition(ExitCondition::EnemyDead(value)),
+                            b"vereist_vlag" => exit.with_condition(ExitCondition::Flag(value)),
+                            b"melding" => exit.with_blocked_message(value),
+                            _ => exit,
+                        };
+                    }
+                    current_locatie.uitgangen.push(exit);
+                }
+                Ok(Event::Start(ref e)) if e.name().0 == b"houder" => {
+                    open_houders.push(Self::parse_houder(e, current_locatie.id, &malformed)?);
                 }
-                Ok(Event::Text(ref e)) => {
-                    if e.len() > 30 {
-                        current_locatie.beschrijving = e.unescape().unwrap().to_string();
+                Ok(Event::Empty(ref e)) if e.name().0 == b"houder" => {
+                    let houder = Self::parse_houder(e, current_locatie.id, &malformed)?;
+                    match open_houders.last_mut() {
+                        Some(parent) => parent.houders.push(houder),
+                        None => current_locatie.houders.push(houder),
                     }
                 }
-                Ok(Event::End(ref e)) => {
-                    if e.name().0 == b"locatie" {
-                        locaties.push(current_locatie.clone());
+                Ok(Event::End(ref e)) if e.name().0 == b"houder" => {
+                    let houder = open_houders.pop().ok_or_else(|| {
+                        malformed(current_locatie.id, "unexpected </houder>".to_string())
+                    })?;
+                    match open_houders.last_mut() {
+                        Some(parent) => parent.houders.push(houder),
+                        None => current_locatie.houders.push(houder),
                     }
                 }
-                Ok(Event::Eof) => break,
-                Err(e) => {
-                    eprintln!("Error reading XML: {}", e);
-                    break;
+                Ok(Event::Start(ref e)) if e.name().0 == b"beschrijving" => {
+                    let mut translation = None;
+                    for attr in e.attributes() {
+                        let Attribute { key, value } =
+                            attr.map_err(|error| malformed(current_locatie.id, error.to_string()))?;
+                        if key.0 == b"taal" {
+                            let code = String::from_utf8_lossy(&value);
+                            translation = Some(Language::from_name(&code).ok_or_else(|| {
+                                malformed(
+                                    current_locatie.id,
+                                    format!("beschrijving has unknown language '{}'", code),
+                                )
+                            })?);
+                        }
+                    }
+                    description = Some(translation);
+                }
+                Ok(Event::Text(ref e)) if description.is_some() => {
+                    let text = e
+                        .unescape()
+                        .map_err(|error| malformed(current_locatie.id, error.to_string()))?
+                        .to_string();
+                    match description {
+                        Some(Some(language)) => current_locatie.vertalingen.push((language, text)),
+                        _ => current_locatie.beschrijving = text,
+                    }
                 }
+                Ok(Event::End(ref e)) if e.name().0 == b"beschrijving" => description = None,
+                Ok(Event::End(ref e)) if e.name().0 == b"locatie" => {
+                    locaties.push(current_locatie.clone());
+                }
+                Ok(Event::Eof) => break,
+                Err(error) => return Err(malformed(current_locatie.id, error.to_string())),
                 _ => (), // Ignore other events
             }
         }
 
-        Self { locaties, db }
+        if locaties.is_empty() {
+            return Err(GameError::EmptyStory {
+                path: xml_path.to_path_buf(),
+            });
+        }
+
+        Ok(Self { locaties, db })
+    }
+
+    fn parse_houder(
+        e: &BytesStart,
+        location: RoomId,
+        malformed: &impl Fn(RoomId, String) -> GameError,
+    ) -> Result<Houder, GameError> {
+        let mut houder = Houder {
+            gewicht: CONTAINER_WEIGHT,
+            ..Houder::default()
+        };
+        let is_yes = |value: &str| ["ja", "true", "1"].contains(&value);
+        for attr in e.attributes() {
+            let Attribute { key, value } =
+                attr.map_err(|error| malformed(location, error.to_string()))?;
+            let value = String::from_utf8_lossy(&value).into_owned();
+            match key.0 {
+                b"naam" => houder.naam = value,
+                b"beschrijving" => houder.beschrijving = value,
+                b"inhoud" => houder.inhoud = Some(value),
+                b"slot" => houder.slot = Some(value),
+                b"vast" => houder.vast = is_yes(&value),
+                b"gewicht" => {
+                    houder.gewicht = value.parse().map_err(|_| {
+                        malformed(location, format!("houder has invalid gewicht '{}'", value))
+                    })?
+                }
+                b"verborgen" => houder.verborgen = is_yes(&value),
+                _ => (),
+            }
+        }
+        if houder.naam.is_empty() {
+            return Err(malformed(location, "houder has no 'naam'".to_string()));
+        }
+        Ok(houder)
+    }
+
+    /// Everything a story author should fix: duplicate ids, dangling and one-way exits,
+    /// unknown objects and enemies, and locations that cannot be reached from the start.
+    pub(crate) fn check(&self) -> Vec<GameError> {
+        let mut problems = Vec::new();
+
+        for (idx, locatie) in self.locaties.iter().enumerate() {
+            if self.locaties[..idx]
+                .iter()
+                .any(|other| other.id == locatie.id)
+            {
+                problems.push(GameError::DuplicateLocation {
+                    location: locatie.id,
+                });
+            }
+        }
+
+        problems.extend(self.dangling_exits());
+
+        for locatie in &self.locaties {
+            for exit in Self::parse_to_exits(locatie) {
+                let Some(other) = self.get_locatie(exit.id()) else {
+                    continue;
+                };
+                // Named exits may lead back any way, e.g. "put" down and "touw" back up.
+                let back = exit.direction().opposite();
+                let leads_back = Self::parse_to_exits(other).iter().any(|other_exit| {
+                    other_exit.id() == locatie.id
+                        && (back == Direction::None
+                            || other_exit.direction() == Direction::None
+                            || other_exit.direction() == back)
+                });
+                if !leads_back {
+                    problems.push(GameError::OneWayExit {
+                        location: locatie.id,
+                        direction: Self::exit_label(&exit),
+                        target: exit.id(),
+                    });
+                }
+            }
+        }
+
+        for locatie in &self.locaties {
+            for exit in Self::parse_to_exits(locatie) {
+                problems.extend(self.exit_problems(locatie.id, &exit));
+            }
+        }
+
+        for locatie in &self.locaties {
+            for objects in [&locatie.objectenzichtbaar, &locatie.objectenverborgen] {
+                for name in Self::split_names(objects) {
+                    if self.db.get_object(name).is_none() {
+                        problems.push(GameError::UnknownObject {
+                            location: Some(locatie.id),
+                            name: name.to_string(),
+                        });
+                    }
+                }
+            }
+            for houder in &locatie.houders {
+                problems.extend(self.houder_problems(locatie.id, houder));
+            }
+            for name in Self::split_names(&locatie.vijand) {
+                if self.db.get_enemy(name).is_none() {
+                    problems.push(GameError::UnknownEnemy {
+                        location: locatie.id,
+                        name: name.to_string(),
+                    });
+                }
+            }
+        }
+        if self.db.get_object(STARTING_WEAPON).is_none() {
+            problems.push(GameError::UnknownObject {
+                location: None,
+                name: STARTING_WEAPON.to_string(),
+            });
+        }
+
+        let reachable = self.reachable_locations();
+        for locatie in &self.locaties {
+            if !reachable.contains(&locatie.id) {
+                problems.push(GameError::UnreachableLocation {
+                    location: locatie.id,
+                });
+            }
+        }
+
+        problems
     }
 
-    fn fill_items(&self, objects: &Option<String>, unique_name: &mut NameGenerator) -> Vec<Item> {
+    /// Keys, required items, guards and flags of `exit` that the story does not have.
+    fn exit_problems(&self, location: RoomId, exit: &Exit) -> Vec<GameError> {
+        let mut problems = Vec::new();
+        let mut items: Vec<&str> = exit.lock().into_iter().collect();
+        for condition in exit.conditions() {
+            match condition {
+                ExitCondition::Carrying(item) => items.push(item),
+                ExitCondition::EnemyDead(name) if self.db.get_enemy(name).is_none() => {
+                    problems.push(GameError::UnknownEnemy {
+                        location,
+                        name: name.clone(),
+                    });
+                }
+                ExitCondition::Flag(flag)
+                    if !self
+                        .locaties
+                        .iter()
+                        .any(|locatie| locatie.vlag.as_ref() == Some(flag)) =>
+                {
+                    problems.push(GameError::UnknownFlag {
+                        location,
+                        direction: Self::exit_label(exit),
+                        flag: flag.clone(),
+                    });
+                }
+                _ => (),
+            }
+        }
+        for item in items {
+            if self.db.get_object(item).is_none() {
+                problems.push(GameError::UnknownObject {
+                    location: Some(location),
+                    name: item.to_string(),
+                });
+            }
+        }
+        problems
+    }
+
+    /// Objects and keys of `houder` and the containers in it that are not in the database.
+    fn houder_problems(&self, location: RoomId, houder: &Houder) -> Vec<GameError> {
+        let mut problems: Vec<GameError> = Self::split_names(&houder.inhoud)
+            .into_iter()
+            .chain(houder.slot.as_deref())
+            .filter(|name| self.db.get_object(name).is_none())
+            .map(|name| GameError::UnknownObject {
+                location: Some(location),
+                name: name.to_string(),
+            })
+            .collect();
+        for inner in &houder.houders {
+            problems.extend(self.houder_problems(location, inner));
+        }
+        problems
+    }
+
+    fn dangling_exits(&self) -> Vec<GameError> {
+        let mut problems = Vec::new();
+        for locatie in &self.locaties {
+            for exit in Self::parse_to_exits(locatie) {
+                if self.get_locatie(exit.id()).is_none() {
+                    problems.push(GameError::BadExitTarget {
+                        location: locatie.id,
+                        direction: Self::exit_label(&exit),
+                        target: exit.id(),
+                    });
+                }
+            }
+        }
+        problems
+    }
+
+    /// The ids of all locations the player can walk to from the first location.
+    fn reachable_locations(&self) -> Vec<RoomId> {
+        let mut reachable = vec![self.locaties[0].id];
+        let mut idx = 0;
+        while idx < reachable.len() {
+            if let Some(locatie) = self.get_locatie(reachable[idx]) {
+                for exit in Self::parse_to_exits(locatie) {
+                    if !reachable.contains(&exit.id()) {
+                        reachable.push(exit.id());
+                    }
+                }
+            }
+            idx += 1;
+        }
+        reachable
+    }
+
+    fn get_locatie(&self, id: RoomId) -> Option<&Locatie> {
+        self.locaties.iter().find(|locatie| locatie.id == id)
+    }
+
+    fn split_names(names: &Option<String>) -> Vec<&str> {
+        match names {
+            Some(names) => names.trim().split(';').collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn fill_items(
+        &self,
+        location: RoomId,
+        objects: &Option<String>,
+        unique_name: &mut NameGenerator,
+        rng: &mut GameRng,
+    ) -> Result<Vec<Item>, GameError> {
         let mut items: Vec<Item> = Vec::new();
-        if let Some(visible_str) = objects.as_ref() {
-            let vis_parts: Vec<&str> = visible_str.trim().split(';').collect();
-
-            for item_name in vis_parts {
-                if let Some(obj) = self.db.get_object(item_name) {
-                    let name = unique_name.generate_name(&obj.name);
-                    let extra_parameters = (
-                        obj.extra_parameters.0 as u32,
-                        obj.extra_parameters.1 as u32,
-                        obj.extra_parameters.2 as u32,
-                    );
-                    items.push(ItemFactory::create_item(
+        for item_name in Self::split_names(objects) {
+            if let Some(obj) = self.db.get_object(item_name) {
+                let name = unique_name.generate_name(&obj.name);
+                let extra_parameters = (
+                    obj.extra_parameters.0 as u32,
+                    obj.extra_parameters.1 as u32,
+                    obj.extra_parameters.2 as u32,
+                );
+                items.push(
+                    ItemFactory::create_item(
                         &name,
                         &obj.description,
                         &obj.type_obj,
                         extra_parameters,
-                    ));
-                } else {
-                    eprintln!("Object {} not found in database", item_name);
-                }
+                        rng,
+                    )
+                    .with_weight(obj.weight as u32),
+                );
+            } else {
+                return Err(GameError::UnknownObject {
+                    location: Some(location),
+                    name: item_name.to_string(),
+                });
             }
         }
-        items
+        Ok(items)
     }
 
-    pub(crate) fn parse_to_exits(locatie: &Locatie) -> Vec<(u32, String)> {
+    /// The compass exits from the attributes of the `locatie`, then its `uitgang` elements.
+    pub(crate) fn parse_to_exits(locatie: &Locatie) -> Vec<Exit> {
         let mut exits = Vec::new();
-        if let Some(north_id) = locatie.noord {
-            exits.push((north_id, "North".to_string()));
-        }
-        if let Some(east_id) = locatie.oost {
-            exits.push((east_id, "East".to_string()));
-        }
-        if let Some(south_id) = locatie.zuid {
-            exits.push((south_id, "South".to_string()));
-        }
-        if let Some(west_id) = locatie.west {
-            exits.push((west_id, "West".to_string()));
+        for (target, direction) in [
+            (locatie.noord, Direction::North),
+            (locatie.oost, Direction::East),
+            (locatie.zuid, Direction::South),
+            (locatie.west, Direction::West),
+        ] {
+            if let Some(target) = target {
+                exits.push(Exit::new(target, direction, None));
+            }
         }
+        exits.extend(locatie.uitgangen.iter().cloned());
+        // An exit to location 0 means there is no exit in that direction.
+        exits.retain(|exit| exit.id() != 0);
         exits
     }
 
-    pub(crate) fn create_rooms(&self, unique_name: &mut NameGenerator) -> Vec<Room> {
+    /// How `check` names an exit, e.g. "North" or "put".
+    fn exit_label(exit: &Exit) -> String {
+        match exit.name() {
+            Some(name) => name.to_string(),
+            None => format!("{:?}", exit.direction()),
+        }
+    }
+
+    /// The rooms of the story, described in `language` where the story has a translation.
+    pub(crate) fn create_rooms(
+        &self,
+        language: Language,
+        unique_name: &mut NameGenerator,
+        rng: &mut GameRng,
+    ) -> Result<Vec<Room>, GameError> {
         let mut rooms: Vec<Room> = Vec::new();
         for story in &self.locaties {
-            let visible_items: Vec<Item> = self.fill_items(&story.objectenzichtbaar, unique_name);
-            let invisible_items: Vec<Item> = self.fill_items(&story.objectenverborgen, unique_name);
+            let mut visible_items: Vec<Item> =
+                self.fill_items(story.id, &story.objectenzichtbaar, unique_name, rng)?;
+            let mut invisible_items: Vec<Item> =
+                self.fill_items(story.id, &story.objectenverborgen, unique_name, rng)?;
+            for houder in &story.houders {
+                let container = self.create_container(story.id, houder, unique_name, rng)?;
+                if houder.verborgen {
+                    invisible_items.push(container);
+                } else {
+                    visible_items.push(container);
+                }
+            }
             let name = unique_name.generate_name(&story.naam);
-            let exits = Self::parse_to_exits(&story);
+            let exits = Self::parse_to_exits(story);
 
+            let description = story
+                .vertalingen
+                .iter()
+                .find(|(translation, _)| *translation == language)
+                .map_or(&story.beschrijving, |(_, text)| text);
             rooms.push(Room::new(
                 story.id,
                 &name,
-                &story.beschrijving,
+                description,
                 &visible_items,
                 &invisible_items,
-                &exits,
+                exits,
+                story.vlag.clone(),
             ));
         }
-        rooms
+        Ok(rooms)
     }
 
-    pub(crate) fn create_enemies(&self, unique_name: &mut NameGenerator) -> Vec<Enemy> {
+    fn create_container(
+        &self,
+        location: RoomId,
+        houder: &Houder,
+        unique_name: &mut NameGenerator,
+        rng: &mut GameRng,
+    ) -> Result<Item, GameError> {
+        let mut contents = self.fill_items(location, &houder.inhoud, unique_name, rng)?;
+        for inner in &houder.houders {
+            contents.push(self.create_container(location, inner, unique_name, rng)?);
+        }
+        Ok(ItemFactory::create_container(
+            &unique_name.generate_name(&houder.naam),
+            &houder.beschrijving,
+            contents,
+            houder.slot.clone(),
+            houder.vast,
+        )
+        .with_weight(houder.gewicht))
+    }
+
+    pub(crate) fn create_enemies(
+        &self,
+        unique_name: &mut NameGenerator,
+        rng: &mut GameRng,
+    ) -> Result<Vec<Enemy>, GameError> {
         let mut enemies: Vec<Enemy> = Vec::new();
 
         for story in &self.locaties {
@@ -213,7 +670,8 @@ impl Story {
                                 found_enemy.minimum_objects as u32,
                                 found_enemy.maximum_objects as u32,
                             ),
-                        );
+                            rng,
+                        )?;
                         enemies.push(Enemy::new(
                             story.id,
                             &name,
@@ -225,47 +683,69 @@ impl Story {
                             &items,
                         ));
                     } else {
-                        eprintln!("enemy not found: {}", single_enemy);
+                        return Err(GameError::UnknownEnemy {
+                            location: story.id,
+                            name: single_enemy.to_string(),
+                        });
                     }
                 }
             }
         }
-        enemies
+        Ok(enemies)
     }
 
     pub(crate) fn generate_random_items(
         &self,
         unique_name: &mut NameGenerator,
         range: &(u32, u32),
-    ) -> Vec<Item> {
+        rng: &mut GameRng,
+    ) -> Result<Vec<Item>, GameError> {
         let mut items: Vec<Item> = Vec::new();
-        let mut rng = thread_rng();
         let max_len = rng.gen_range(range.0..=range.1);
         let mut idx = 0;
         let objects = self.db.get_all_objects();
 
         while idx != max_len {
-            let enemy_obj = objects.choose(&mut rng).unwrap();
+            let enemy_obj = objects
+                .choose(rng)
+                .ok_or_else(|| GameError::DatabaseSchema {
+                    table: "Objecten",
+                    message: "no objects to give enemies".to_string(),
+                })?;
             let name = unique_name.generate_name(&enemy_obj.name);
             let parameters: (u32, u32, u32) = (
                 enemy_obj.extra_parameters.0 as u32,
                 enemy_obj.extra_parameters.1 as u32,
                 enemy_obj.extra_parameters.2 as u32,
             );
-            items.push(ItemFactory::create_item(
-                &name,
-                &enemy_obj.description,
-                &enemy_obj.type_obj,
-                parameters,
-            ));
+            items.push(
+                ItemFactory::create_item(
+                    &name,
+                    &enemy_obj.description,
+                    &enemy_obj.type_obj,
+                    parameters,
+                    rng,
+                )
+                .with_weight(enemy_obj.weight as u32),
+            );
             idx += 1;
         }
 
-        items
+        Ok(items)
     }
 
-    pub(crate) fn get_player_starting_weapen(&self, unique_name: &mut NameGenerator) -> Item {
-        let obj = self.db.get_object("dolk").unwrap();
+    pub(crate) fn get_player_starting_weapen(
+        &self,
+        unique_name: &mut NameGenerator,
+        rng: &mut GameRng,
+    ) -> Result<Item, GameError> {
+        let obj = self
+            .db
+            .get_object(STARTING_WEAPON)
+            .ok_or_else(|| GameError::UnknownObject {
+                location: None,
+                name: STARTING_WEAPON.to_string(),
+            })?;
         let name = unique_name.generate_name(&obj.name);
         let parameters: (u32, u32, u32) = (
             obj.extra_parameters.0 as u32,
@@ -273,6 +753,248 @@ impl Story {
             obj.extra_parameters.2 as u32,
         );
 
-        ItemFactory::create_item(&name, &obj.description, &obj.type_obj, parameters)
+        Ok(
+            ItemFactory::create_item(&name, &obj.description, &obj.type_obj, parameters, rng)
+                .with_weight(obj.weight as u32),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_story::{TempDir, TempStory, DATABASE};
+    use rand::SeedableRng;
+
+    type Loader = fn(&Path, &Path) -> Result<Story, GameError>;
+
+    fn load_story(name: &str, xml: &str, loader: Loader) -> Result<Story, GameError> {
+        let story = TempStory::new(name, xml);
+        loader(story.path(), Path::new(DATABASE))
+    }
+
+    #[test]
+    fn test_missing_story_file() {
+        let story = Story::new(Path::new("does_not_exist.xml"), Path::new(DATABASE));
+        assert!(matches!(story, Err(GameError::MissingFile { .. })));
+    }
+
+    #[test]
+    fn test_unknown_object_type() {
+        let directory = TempDir::new("unknown_type");
+        let database = directory.database();
+        let connection = sqlite::open(&database).unwrap();
+        connection
+            .execute(
+                "INSERT INTO Objecten (naam, omschrijving, type) VALUES ('toverstaf', 'Een staf.', 'staf')",
+            )
+            .unwrap();
+        drop(connection);
+        let story = TempStory::new("unknown_type", "<locaties></locaties>");
+
+        let story = Story::load(story.path(), &database);
+        assert!(matches!(
+            story,
+            Err(GameError::UnknownObjectType { object, object_type })
+                if object == "toverstaf" && object_type == "staf"
+        ));
+    }
+
+    #[test]
+    fn test_exit_to_missing_location() {
+        let story = load_story(
+            "bad_exit",
+            r#"<locaties><locatie id="1" noord="7" naam="Poort"></locatie></locaties>"#,
+            Story::new,
+        );
+        assert!(matches!(
+            story,
+            Err(GameError::BadExitTarget {
+                location: 1,
+                target: 7,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_object_reports_its_location() {
+        let story = load_story(
+            "unknown_object",
+            r#"<locaties><locatie id="3" objectenzichtbaar="gouden eend" naam="Poort"></locatie></locaties>"#,
+            Story::new,
+        )
+        .unwrap();
+        let mut rng = GameRng::seed_from_u64(0);
+        let rooms = story.create_rooms(Language::English, &mut NameGenerator::new(), &mut rng);
+        assert!(matches!(
+            rooms,
+            Err(GameError::UnknownObject {
+                location: Some(3),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_shipped_story_has_no_problems() {
+        let story =
+            Story::load(Path::new("../assets/kasteelruine.xml"), Path::new(DATABASE)).unwrap();
+        assert!(story.check().is_empty());
+    }
+
+    #[test]
+    fn test_descriptions_per_language() {
+        let xml = |language: &str| {
+            format!(
+                r#"<locaties><locatie id="1" naam="Poort">
+                    <beschrijving>Een poort.</beschrijving>
+                    <beschrijving taal="{}">A gate.</beschrijving>
+                </locatie></locaties>"#,
+                language
+            )
+        };
+        let story = load_story("languages", &xml("en"), Story::load).unwrap();
+        assert_eq!(story.locaties[0].beschrijving, "Een poort.");
+        assert_eq!(
+            story.locaties[0].vertalingen,
+            [(Language::English, "A gate.".to_string())]
+        );
+
+        let story = load_story("unknown_language", &xml("fr"), Story::load);
+        assert!(matches!(
+            story,
+            Err(GameError::MalformedXml {
+                location: Some(1),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_named_and_vertical_exits() {
+        let story = load_story(
+            "exits",
+            r#"<locaties>
+                <locatie id="1" naam="Waterput"><uitgang naar="2" richting="omlaag" naam="put"/></locatie>
+                <locatie id="2" naam="Bodem"><uitgang naar="1" naam="touw"></uitgang></locatie>
+                <locatie id="3" naam="Toren" noord="1"><uitgang naar="1" richting="up"/></locatie>
+            </locaties>"#,
+            Story::load,
+        )
+        .unwrap();
+        let exits = Story::parse_to_exits(&story.locaties[0]);
+        assert_eq!(exits[0].direction(), Direction::Down);
+        assert_eq!(exits[0].name(), Some("put"));
+
+        let problems: Vec<String> = story.check().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            problems,
+            [
+                "location 3 has exit North to location 1, which has no exit back",
+                "location 3 has exit Up to location 1, which has no exit back",
+                "location 3 cannot be reached from the first location",
+            ]
+        );
+
+        let story = load_story(
+            "exit_without_way",
+            r#"<locaties><locatie id="1" naam="Poort"><uitgang naar="1"/></locatie></locaties>"#,
+            Story::load,
+        );
+        assert!(matches!(story, Err(GameError::MalformedXml { .. })));
+    }
+
+    #[test]
+    fn test_locked_hidden_and_conditional_exits() {
+        let story = load_story(
+            "conditions",
+            r#"<locaties>
+                <locatie id="1" naam="Poort" vlag="poort">
+                    <uitgang naar="2" richting="noord" slot="dolk" verborgen="ja"/>
+                    <uitgang naar="2" naam="hek" bewaakt_door="Goblin" vereist_vlag="poort"/>
+                    <uitgang naar="2" richting="oost" vereist_voorwerp="gouden eend" melding="Te smal."/>
+                </locatie>
+                <locatie id="2" zuid="1" naam="Hof">
+                    <uitgang naar="1" richting="west" bewaakt_door="Draakje" vereist_vlag="hof"/>
+                </locatie>
+            </locaties>"#,
+            Story::load,
+        )
+        .unwrap();
+        let exits = Story::parse_to_exits(&story.locaties[0]);
+        assert_eq!(exits[0].lock(), Some("dolk"));
+        assert_eq!(
+            exits[1].conditions(),
+            [
+                ExitCondition::EnemyDead("Goblin".to_string()),
+                ExitCondition::Flag("poort".to_string())
+            ]
+        );
+        assert_eq!(exits[2].blocked_message(), Some("Te smal."));
+
+        let problems: Vec<String> = story.check().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            problems,
+            [
+                "location 1 refers to object 'gouden eend', which is not in the database",
+                "location 2 refers to enemy 'Draakje', which is not in the database",
+                "location 2 has exit West waiting for flag 'hof', which no location sets",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_containers() {
+        let story = load_story(
+            "containers",
+            r#"<locaties><locatie id="1" naam="Kelder">
+                <houder naam="kist" inhoud="dolk" slot="speer" vast="ja">
+                    <houder naam="zakje" inhoud="gouden eend"/>
+                </houder>
+                <houder naam="tas" verborgen="ja"/>
+            </locatie></locaties>"#,
+            Story::load,
+        )
+        .unwrap();
+        let problems: Vec<String> = story.check().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            problems,
+            ["location 1 refers to object 'gouden eend', which is not in the database"]
+        );
+
+        let houders = &story.locaties[0].houders;
+        assert_eq!(houders.len(), 2);
+        assert_eq!(houders[0].houders[0].naam, "zakje");
+        assert!(houders[0].vast && houders[1].verborgen);
+    }
+
+    #[test]
+    fn test_check_reports_every_problem() {
+        let story = load_story(
+            "check",
+            r#"<locaties>
+                <locatie id="1" noord="2" oost="9" naam="Poort"></locatie>
+                <locatie id="2" vijand="Draakje" objectenverborgen="dolk;gouden eend" naam="Hof"></locatie>
+                <locatie id="3" naam="Toren"></locatie>
+                <locatie id="3" naam="Kelder"></locatie>
+            </locaties>"#,
+            Story::load,
+        )
+        .unwrap();
+
+        let problems: Vec<String> = story.check().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            problems,
+            [
+                "location id 3 is used more than once",
+                "location 1 has exit East to location 9, which does not exist",
+                "location 1 has exit North to location 2, which has no exit back",
+                "location 2 refers to object 'gouden eend', which is not in the database",
+                "location 2 refers to enemy 'Draakje', which is not in the database",
+                "location 3 cannot be reached from the first location",
+                "location 3 cannot be reached from the first location",
+            ]
+        );
     }
 }
@@ -0,0 +1,92 @@
+//! Stories written to temporary files for tests, games played on them and temporary
+//! directories to save those games in.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::{
+    console::console::{MemoryInput, MemoryOutput},
+    Game, GameOptions,
+};
+
+/// The shipped database, the stories in tests refer to its objects and enemies.
+pub(crate) const DATABASE: &str = "../assets/kerkersendraken.db";
+
+/// A story in a temporary file, removed again when dropped, also when an assertion fails.
+pub(crate) struct TempStory {
+    path: PathBuf,
+}
+
+impl TempStory {
+    /// Writes `xml` to a file named after `name`, which has to be unique among the tests.
+    pub(crate) fn new(name: &str, xml: &str) -> Self {
+        let path = env::temp_dir().join(format!("kerkers_{}_{}.xml", name, process::id()));
+        fs::write(&path, xml).unwrap();
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Options to play the story with the shipped database.
+    pub(crate) fn options(&self) -> GameOptions {
+        GameOptions {
+            story_path: Some(self.path.clone()),
+            database_path: Some(PathBuf::from(DATABASE)),
+            ..GameOptions::default()
+        }
+    }
+}
+
+impl Drop for TempStory {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A temporary directory, removed with everything in it when dropped.
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Names the directory after `name`, which has to be unique among the tests. It is only
+    /// created once something is written to it.
+    pub(crate) fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("kerkers_{}_{}", name, process::id()));
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Copies the shipped database into the directory, for tests that change it.
+    pub(crate) fn database(&self) -> PathBuf {
+        fs::create_dir_all(&self.path).unwrap();
+        let path = self.path.join("kerkersendraken.db");
+        fs::copy(DATABASE, &path).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Plays the story `xml` with `input`, the player's name first, and returns all the game printed.
+pub(crate) fn play_story(name: &str, xml: &str, input: &[&str]) -> String {
+    let story = TempStory::new(name, xml);
+    let output = MemoryOutput::new();
+    let input = MemoryInput::new(input.iter().copied());
+    let mut game =
+        Game::with_options(story.options(), Box::new(input), Box::new(output.clone())).unwrap();
+    game.run();
+    output.contents()
+}
@@ -1,59 +1,115 @@
-use core::panic;
-
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::console;
-use crate::console_output;
+use crate::console::console::{Console, Style};
 use crate::enemy::Enemy;
+use crate::error::GameError;
 use crate::name_generator::NameGenerator;
+use crate::objects::{Container, Item};
 use crate::player::*;
+use crate::presentation::base_name;
 use crate::room::*;
 use crate::story::Story;
+use crate::{console_message, console_output, message};
+
+/// The random number generator every random decision in the game is drawn from.
+/// Its state is part of a save, so a loaded game continues with the same rolls.
+pub(crate) type GameRng = ChaCha12Rng;
 
+#[derive(Serialize, Deserialize)]
 pub(crate) struct World {
     rooms: Vec<Room>,
     enemies: Vec<Enemy>,
     player: Player,
     current_room: RoomId,
+    name_generator: NameGenerator,
+    seed: u64,
+    rng: GameRng,
+    /// Flags set by entering rooms, or by commands through `Game::set_flag`.
+    flags: Vec<String>,
+    /// Where the world prints to, the console of the game. It is not saved,
+    /// `Game::replace_world` hands a loaded world the console of the game that loads it.
+    #[serde(skip)]
+    console: Console,
 }
 
 impl World {
-    pub(crate) fn new() -> Self {
-        let story_parser = Story::new("../assets/kasteelruine.xml");
+    /// Creates the world of `story` for a new game, drawing its random names and stats from `seed`.
+    pub(crate) fn new(
+        player_name: &str,
+        seed: u64,
+        story_parser: &Story,
+        console: Console,
+    ) -> Result<Self, GameError> {
+        let mut rng = GameRng::seed_from_u64(seed);
         let mut unique_name_generator = NameGenerator::new();
-        let created_rooms = story_parser.create_rooms(&mut unique_name_generator);
+        let created_rooms =
+            story_parser.create_rooms(console.language(), &mut unique_name_generator, &mut rng)?;
         let curr_room = created_rooms[0].get_id();
-        let created_enemies = story_parser.create_enemies(&mut unique_name_generator);
+        let created_enemies = story_parser.create_enemies(&mut unique_name_generator, &mut rng)?;
         let player_start_weapon =
-            story_parser.get_player_starting_weapen(&mut unique_name_generator);
-
-        let player_name = console::console::read_input();
+            story_parser.get_player_starting_weapen(&mut unique_name_generator, &mut rng)?;
 
-        Self {
+        let mut world = Self {
             rooms: created_rooms,
             enemies: created_enemies,
-            player: Player::new(player_name.as_str(), &player_start_weapon),
+            player: Player::new(player_name, &player_start_weapon),
             current_room: curr_room,
-        }
+            name_generator: unique_name_generator,
+            seed,
+            rng,
+            flags: Vec::new(),
+            console,
+        };
+        world.set_room_flag();
+        Ok(world)
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub(crate) fn console(&self) -> &Console {
+        &self.console
+    }
+
+    pub(crate) fn set_console(&mut self, console: Console) {
+        self.console = console;
+    }
+
+    /// A location the player, an enemy or an exit refers to that is not in the world, if any.
+    /// `room_index` relies on there being none, which `Story::new` and `save::decode` check.
+    pub(crate) fn unknown_location(&self) -> Option<RoomId> {
+        let exits = self
+            .rooms
+            .iter()
+            .flat_map(|room| room.get_exits().iter().map(Exit::id));
+        let enemies = self.enemies.iter().map(Enemy::room_id);
+        std::iter::once(self.current_room)
+            .chain(enemies)
+            .chain(exits)
+            .find(|id| !self.rooms.iter().any(|room| room.get_id() == *id))
+    }
+
+    /// Every id the world hands out comes from the story, and `Story::new` rejects exits
+    /// to locations that do not exist, so the lookup always succeeds. Saves are checked with
+    /// `unknown_location` when they are loaded.
+    fn room_index(&self, id: RoomId) -> usize {
+        self.rooms
+            .iter()
+            .position(|room| room.get_id() == id)
+            .unwrap_or_else(|| unreachable!("location {} is not in the story", id))
     }
 
     pub(crate) fn get_current_room(&self) -> &Room {
-        for room in &self.rooms {
-            if room.get_id() == self.current_room {
-                return &room;
-            }
-        }
-        panic!("Could not find room");
+        &self.rooms[self.room_index(self.current_room)]
     }
 
     pub(crate) fn get_current_room_mut(&mut self) -> &mut Room {
-        for room in self.rooms.iter_mut() {
-            if room.get_id() == self.current_room {
-                return room;
-            }
-        }
-        panic!("Could not find room");
+        let idx = self.room_index(self.current_room);
+        &mut self.rooms[idx]
     }
 
     pub(crate) fn get_player(&self) -> &Player {
@@ -63,63 +119,457 @@ impl World {
     pub(crate) fn get_player_mut(&mut self) -> &mut Player {
         &mut self.player
     }
+
+    pub(crate) fn player_hit(&mut self) -> u32 {
+        self.player.hit(&self.console, &mut self.rng)
+    }
+
+    /// The names of the containers in the room, then those in the backpack, each followed by
+    /// the containers inside it unless it is locked.
+    pub(crate) fn container_names(&self) -> Vec<String> {
+        self.get_current_room()
+            .visible_items()
+            .iter()
+            .chain(self.player.backpack_items())
+            .flat_map(Item::container_names)
+            .collect()
+    }
+
+    /// A container in the room or in the backpack, or inside one of those, see `container_names`.
+    pub(crate) fn container(&self, name: &str) -> Option<&Container> {
+        self.get_current_room()
+            .visible_items()
+            .iter()
+            .chain(self.player.backpack_items())
+            .find_map(|item| item.find_container(name))
+    }
+
+    pub(crate) fn container_mut(&mut self, name: &str) -> Option<&mut Container> {
+        let idx = self.room_index(self.current_room);
+        self.rooms[idx]
+            .visible_items_mut()
+            .iter_mut()
+            .chain(self.player.backpack_items_mut())
+            .find_map(|item| item.find_container_mut(name))
+    }
+
+    pub(crate) fn fill_player_backpack(&mut self, item: Item) {
+        self.player
+            .fill_backpack(&self.console, item, &mut self.rng);
+    }
+
+    pub(crate) fn player_consume_potion(&mut self, item_name: &str) {
+        if let Some(locations) = self
+            .player
+            .consume_potion(&self.console, item_name, &mut self.rng)
+        {
+            self.teleport_player(locations);
+        }
+    }
+
+    /// Walks `locations` random steps through the exits, preferring rooms not visited on the
+    /// way so the player does not end up back where they started. Only exits the player could
+    /// take themselves are used, hidden, locked and blocked ones are left out.
+    fn teleport_player(&mut self, locations: u32) {
+        let mut visited = vec![self.current_room];
+        for _ in 0..locations {
+            let idx = self.room_index(visited[visited.len() - 1]);
+            let exits: Vec<RoomId> = self.rooms[idx]
+                .visible_exits()
+                .filter(|exit| self.exit_blocked(exit).is_none())
+                .map(Exit::id)
+                .collect();
+            let unvisited: Vec<RoomId> = exits
+                .iter()
+                .copied()
+                .filter(|id| !visited.contains(id))
+                .collect();
+            let chosen_exit = if unvisited.is_empty() {
+                exits.choose(&mut self.rng)
+            } else {
+                unvisited.choose(&mut self.rng)
+            };
+            match chosen_exit {
+                Some(id) => visited.push(*id),
+                None => break,
+            }
+        }
+
+        let destination = visited[visited.len() - 1];
+        console_message!(self.console, Teleported, visited.len() - 1);
+        self.goto_next_room(destination);
+        // Drinking is a free turn, but arriving is not: enemies attack like after Go.
+        self.enemies_attack();
+    }
     pub(crate) fn goto_next_room(&mut self, new_room: RoomId) {
         self.current_room = new_room;
+        console_message!(self.console, GoingTo, new_room);
+        self.set_room_flag();
+    }
+
+    /// Sets the flag of the current room, if it has one.
+    fn set_room_flag(&mut self) {
+        if let Some(flag) = self.get_current_room().flag() {
+            let flag = flag.to_string();
+            self.set_flag(&flag);
+        }
+    }
+
+    pub(crate) fn set_flag(&mut self, flag: &str) {
+        if !self.has_flag(flag) {
+            self.flags.push(flag.to_string());
+        }
+    }
+
+    pub(crate) fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|set| set == flag)
+    }
+
+    /// Why the player cannot take `exit` right now, or `None` when they can. A guarded exit
+    /// stays closed until its enemy is dead, wherever that enemy has wandered off to.
+    pub(crate) fn exit_blocked(&self, exit: &Exit) -> Option<String> {
+        let console = &self.console;
+        let name = exit.label(console.language());
+        if exit.lock().is_some() {
+            return Some(message!(console, ExitLocked, name));
+        }
+        let blocked = exit
+            .conditions()
+            .iter()
+            .find_map(|condition| match condition {
+                ExitCondition::Carrying(item) => {
+                    let carried = self
+                        .player
+                        .carried_items()
+                        .iter()
+                        .any(|carried| base_name(carried.name()) == item);
+                    (!carried).then(|| message!(console, ExitNeedsItem, name, item))
+                }
+                ExitCondition::EnemyDead(enemy_name) => self
+                    .enemies
+                    .iter()
+                    .find(|enemy| enemy.hp() > 0 && base_name(enemy.name()) == enemy_name)
+                    .map(|enemy| {
+                        message!(
+                            console,
+                            ExitGuarded,
+                            console.styled(Style::Enemy, enemy.name()),
+                            name
+                        )
+                    }),
+                ExitCondition::Flag(flag) => {
+                    (!self.has_flag(flag)).then(|| message!(console, ExitClosed, name))
+                }
+            })?;
+        Some(match exit.blocked_message() {
+            Some(text) => format!("{}\n", text),
+            None => blocked,
+        })
+    }
+
+    /// Unlocks `exit` of the current room, and the way back when `key` fits that too.
+    pub(crate) fn unlock_exit(&mut self, exit: &Exit, key: &str) {
+        let current_room = self.current_room;
+        self.get_current_room_mut().unlock_exits_to(exit.id(), key);
+        let idx = self.room_index(exit.id());
+        self.rooms[idx].unlock_exits_to(current_room, key);
     }
 
     pub(crate) fn show(&self) {
-        let idx = (self.current_room - 1) as usize;
-        let room = self.rooms.get(idx).unwrap();
-        room.show();
+        self.get_current_room().show(&self.console, &self.rooms);
 
         for enemy in &self.enemies {
             if enemy.room_id() == self.current_room {
-                enemy.show();
+                enemy.show(&self.console);
             }
         }
     }
 
     pub(crate) fn move_enemy_items_to_current_room(&mut self, name: &str) {
+        let idx = self.room_index(self.current_room);
         for enemy in self.enemies.iter_mut() {
             if enemy.room_id() == self.current_room && enemy.name() == name {
                 if enemy.hp() == 0 {
-                    let idx = (self.current_room - 1) as usize;
                     self.rooms[idx].append_items(&mut enemy.move_items());
                 } else {
-                    enemy.show();
+                    enemy.show(&self.console);
                 }
             }
         }
     }
 
     pub(crate) fn enemies_move(&mut self) {
-        let mut rng = thread_rng();
-
-        for enemy in self.enemies.iter_mut() {
-            let id = enemy.room_id();
-            let room_idx = (id - 1) as usize;
-            let room = self.rooms.get(room_idx).unwrap();
-            let chosen_exit = room.get_exits().choose(&mut rng).unwrap();
-            enemy.set_position(chosen_exit.id());
+        for idx in 0..self.enemies.len() {
+            let room_idx = self.room_index(self.enemies[idx].room_id());
+            // Enemies in a room without exits, or with only locked ones, stay where they are.
+            let exits: Vec<&Exit> = self.rooms[room_idx]
+                .get_exits()
+                .iter()
+                .filter(|exit| exit.lock().is_none())
+                .collect();
+            if let Some(chosen_exit) = exits.choose(&mut self.rng) {
+                self.enemies[idx].set_position(chosen_exit.id());
+            }
         }
     }
 
     pub(crate) fn enemies_attack(&mut self) {
         for enemy in self.enemies.iter_mut() {
             if enemy.room_id() == self.current_room {
-                let enemy_damage = enemy.attack();
-                self.player.take_dmg(enemy_damage);
+                let enemy_damage = enemy.attack(&self.console, &mut self.rng);
+                self.player
+                    .take_dmg(&self.console, enemy_damage, &mut self.rng);
             }
         }
     }
 
+    pub(crate) fn enemy_names_in_current_room(&self) -> Vec<String> {
+        self.enemies
+            .iter()
+            .filter(|enemy| enemy.room_id() == self.current_room)
+            .map(|enemy| enemy.name().to_string())
+            .collect()
+    }
+
     pub(crate) fn player_attack_enemy(&mut self, player_dmg: u32, name: &str) {
         for enemy in self.enemies.iter_mut() {
             if (enemy.room_id() == self.current_room) && enemy.name() == name {
-                enemy.take_dmg(player_dmg);
+                enemy.take_dmg(&self.console, player_dmg);
                 return;
             }
         }
-        console_output!("No enemies found with that name: {}\n", name);
+        console_output!(
+            self.console,
+            "{}",
+            self.console
+                .styled(Style::Warning, message!(self.console, NoEnemyNamed, name))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_story::play_story;
+
+    #[test]
+    fn test_locked_and_hidden_exits() {
+        let transcript = play_story(
+            "locked",
+            r#"<locaties>
+                <locatie id="1" objectenzichtbaar="hellebaard" naam="Poort">
+                    <uitgang naar="2" richting="noord" slot="hellebaard"/>
+                    <uitgang naar="3" naam="luik" verborgen="ja"/>
+                </locatie>
+                <locatie id="2" zuid="1" naam="Hof"></locatie>
+                <locatie id="3" naam="Kelder"><uitgang naar="1" richting="omhoog"/></locatie>
+            </locaties>"#,
+            &[
+                "Tester",
+                "Go north",
+                "Unlock north with hellebaard",
+                "Take hellebaard",
+                "Unlock north",
+                "Go north",
+                "Go south",
+                "Go luik",
+                "Search",
+                "Go luik",
+                "Quit",
+            ],
+        );
+        assert!(transcript.contains("The exit north is locked.\n"));
+        assert!(transcript.contains("You have nothing that unlocks the exit north.\n"));
+        assert!(transcript.contains("You unlock the exit north with hellebaard.\n"));
+        assert!(transcript.contains("Going to 2\n"));
+        assert!(transcript.contains("You found a hidden exit: luik\n"));
+        assert_eq!(transcript.matches("Going to 3\n").count(), 1);
+    }
+
+    #[test]
+    fn test_guards_and_start_flag() {
+        let transcript = play_story(
+            "guards",
+            r#"<locaties>
+                <locatie id="1" vlag="binnen" naam="Poort">
+                    <uitgang naar="2" richting="west" bewaakt_door="Trol"/>
+                    <uitgang naar="3" richting="noord" vereist_vlag="binnen"/>
+                </locatie>
+                <locatie id="2" oost="1" naam="Grot"></locatie>
+                <locatie id="3" zuid="1" vijand="Trol" naam="Hol"></locatie>
+            </locaties>"#,
+            &["Tester", "Godmode", "Go west", "Go north", "Quit"],
+        );
+        assert!(transcript.contains("Trol blocks the exit west.\n"));
+        assert!(!transcript.contains("Going to 2\n"));
+        assert!(transcript.contains("Going to 3\n"));
+    }
+
+    #[test]
+    fn test_teleport_only_takes_open_exits() {
+        let transcript = play_story(
+            "teleport_exits",
+            r#"<locaties>
+                <locatie id="1" objectenzichtbaar="kleine teleportatiedrank" naam="Poort">
+                    <uitgang naar="2" richting="noord" slot="hellebaard"/>
+                    <uitgang naar="3" naam="luik" verborgen="ja"/>
+                    <uitgang naar="4" richting="west" bewaakt_door="Trol"/>
+                    <uitgang naar="5" richting="oost" vereist_voorwerp="hellebaard"/>
+                    <uitgang naar="6" richting="zuid"/>
+                </locatie>
+                <locatie id="2" naam="Hof"></locatie>
+                <locatie id="3" naam="Kelder"></locatie>
+                <locatie id="4" vijand="Trol" naam="Grot"></locatie>
+                <locatie id="5" naam="Zaal"></locatie>
+                <locatie id="6" naam="Tuin"></locatie>
+            </locaties>"#,
+            &[
+                "Tester",
+                "Take kleine teleportatiedrank",
+                "Consume kleine teleportatiedrank",
+                "Quit",
+            ],
+        );
+        assert!(transcript.contains("Going to 6\n"));
+        for room in 2..=5 {
+            assert!(!transcript.contains(&format!("Going to {}\n", room)));
+        }
+    }
+
+    #[test]
+    fn test_teleport_arrival_is_attacked() {
+        let transcript = play_story(
+            "teleport_attack",
+            r#"<locaties>
+                <locatie id="1" objectenzichtbaar="kleine teleportatiedrank" naam="Poort">
+                    <uitgang naar="2" richting="noord"/>
+                </locatie>
+                <locatie id="2" zuid="1" vijand="Trol" naam="Grot"></locatie>
+            </locaties>"#,
+            &[
+                "Tester",
+                "Take kleine teleportatiedrank",
+                "Consume kleine teleportatiedrank",
+                "Quit",
+            ],
+        );
+        let arrival = transcript.split("Going to 2\n").nth(1).unwrap();
+        assert!(arrival.contains(" the player"));
+    }
+
+    #[test]
+    fn test_containers() {
+        let transcript = play_story(
+            "containers",
+            r#"<locaties><locatie id="1" objectenzichtbaar="hellebaard" naam="Kelder">
+                <houder naam="kist" inhoud="speer" slot="hellebaard" vast="ja">
+                    <houder naam="zakje" inhoud="klein levenselixer"/>
+                </houder>
+            </locatie></locaties>"#,
+            &[
+                "Tester",
+                "Take speer from kist",
+                "Take hellebaard",
+                "Open kist",
+                "Take zakje from kist",
+                "Put dolk in zakje",
+                "Look in zakje",
+                "Take kist",
+                "Quit",
+            ],
+        );
+        assert!(transcript.contains("Container 'kist' is locked.\n"));
+        assert!(transcript.contains("You unlock kist with hellebaard.\n"));
+        assert!(transcript.contains("Container 'kist' contains speer and zakje.\n"));
+        assert!(transcript.contains("Item 'zakje' has been moved to your backpack.\n"));
+        assert!(transcript.contains("Container 'zakje' contains klein levenselixer and dolk.\n"));
+        assert!(transcript.contains("Container 'kist' is too heavy to carry.\n"));
+    }
+
+    #[test]
+    fn test_open_unknown_name() {
+        let transcript = play_story(
+            "open_unknown",
+            r#"<locaties>
+                <locatie id="1" naam="Poort"><uitgang naar="2" richting="noord" slot="hellebaard"/></locatie>
+                <locatie id="2" zuid="1" naam="Hof"></locatie>
+            </locaties>"#,
+            &["Tester", "Open kist", "Open north", "Quit"],
+        );
+        assert!(transcript.contains("There is no container 'kist' here.\n"));
+        assert!(!transcript.contains("Wrong goto direction"));
+        assert!(transcript.contains("You have nothing that unlocks the exit north.\n"));
+    }
+
+    #[test]
+    fn test_nested_containers() {
+        let transcript = play_story(
+            "nested_containers",
+            r#"<locaties><locatie id="1" objectenzichtbaar="hellebaard" naam="Kelder">
+                <houder naam="kist" slot="hellebaard" vast="ja">
+                    <houder naam="zakje" inhoud="klein levenselixer"/>
+                </houder>
+                <houder naam="tas"><houder naam="buidel"/></houder>
+            </locatie></locaties>"#,
+            &[
+                "Tester",
+                "Open kist",
+                "Look in zakje",
+                "Take hellebaard",
+                "Open kist",
+                "Take klein levenselixer from zakje",
+                "Put dolk in zakje",
+                "Look in zakje",
+                "Take tas",
+                "Put tas in buidel",
+                "Look in tas",
+                "Quit",
+            ],
+        );
+        assert!(transcript.contains("You have nothing that unlocks kist.\n"));
+        assert!(!transcript.contains("Container 'kist' is locked.\n"));
+        assert!(transcript.contains("There is no container 'zakje' here.\n"));
+        assert!(transcript.contains("Item 'klein levenselixer' has been moved to your backpack.\n"));
+        assert!(transcript.contains("Container 'zakje' contains dolk.\n"));
+        assert!(transcript.contains("Container 'tas' cannot go in itself.\n"));
+        assert!(transcript.contains("Container 'tas' contains buidel.\n"));
+    }
+
+    #[test]
+    fn test_carry_capacity() {
+        let transcript = play_story(
+            "capacity",
+            r#"<locaties><locatie id="1" objectenzichtbaar="harnas;hellebaard;groot levenselixer;speer" naam="Wapenkamer">
+            </locatie></locaties>"#,
+            &["Tester", "Take all", "SeePlayer", "Quit"],
+        );
+        assert!(transcript.contains("your attack chance drops by 15%"));
+        assert!(transcript.contains("Item 'speer' is too heavy, you carry 25 of at most 25.\n"));
+        assert!(transcript.contains("Attack Chance:  25%\nWeight:         25/25, encumbered\n"));
+        assert!(!transcript.contains("Item 'speer' has been moved"));
+    }
+
+    #[test]
+    fn test_stackable_potions() {
+        let transcript = play_story(
+            "stacks",
+            r#"<locaties><locatie id="1" objectenzichtbaar="klein levenselixer;klein levenselixer;klein levenselixer;klein levenselixer" naam="Voorraadkamer">
+            </locatie></locaties>"#,
+            &[
+                "Tester",
+                "Take 3 klein levenselixer",
+                "Take klein levenselixer",
+                "SeePlayer",
+                "Put 2 klein levenselixer",
+                "Consume klein levenselixer",
+                "SeePlayer",
+                "Look",
+                "Quit",
+            ],
+        );
+        assert!(transcript.contains("Item '3 x klein levenselixer' has been moved"));
+        assert!(transcript.contains("Backpack:       4 x klein levenselixer\n"));
+        assert!(transcript.contains("Item '2 x klein levenselixer' has been removed"));
+        assert!(transcript.contains("Backpack:       klein levenselixer\n"));
+        assert!(transcript.contains("Items: 2 x klein levenselixer"));
     }
 }
@@ -0,0 +1,50 @@
+use std::{env, fs, path::PathBuf};
+
+use kerkers_en_draken::{Game, GameOptions, MemoryOutput, ScriptInput, SCRIPT_SEED};
+
+/// Runs `tests/golden/<name>.script` with the fixed script seed and compares the output with
+/// `tests/golden/<name>.transcript`. Set `UPDATE_GOLDEN=1` to rewrite the transcript instead.
+fn check_transcript(name: &str) {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let script = directory.join(format!("{}.script", name));
+    let golden = directory.join(format!("{}.transcript", name));
+
+    let output = MemoryOutput::new();
+    let input = ScriptInput::from_file(&script).expect("Failed to read script");
+    let options = GameOptions {
+        seed: Some(SCRIPT_SEED),
+        ..GameOptions::default()
+    };
+    let mut game = Game::with_options(options, Box::new(input), Box::new(output.clone()))
+        .expect("Failed to load the world");
+    game.run();
+    let transcript = output.contents();
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, transcript).expect("Failed to write transcript");
+        return;
+    }
+    let expected = fs::read_to_string(&golden).expect("Failed to read transcript");
+    assert!(
+        transcript == expected,
+        "Transcript for '{}' differs from {}, rerun with UPDATE_GOLDEN=1 if the change is intended\n\n{}",
+        name,
+        golden.display(),
+        transcript
+    );
+}
+
+#[test]
+fn test_explore_transcript() {
+    check_transcript("explore");
+}
+
+#[test]
+fn test_combat_transcript() {
+    check_transcript("combat");
+}
+
+#[test]
+fn test_teleport_transcript() {
+    check_transcript("teleport");
+}
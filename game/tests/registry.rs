@@ -0,0 +1,70 @@
+use kerkers_en_draken::*;
+
+#[test]
+fn test_downstream_command_is_listed_and_runs() {
+    let output = MemoryOutput::new();
+    let input = MemoryInput::new(["Tester", "Help", "dance wildly", "Quit"]);
+    let mut game =
+        Game::with_io(Box::new(input), Box::new(output.clone())).expect("Failed to load the world");
+    game.register_command(CommandSpec {
+        name: "Dance",
+        verbs: &["dance", "dans"],
+        argument: Argument::Optional("Style"),
+        help: "Dance in the ruins",
+        turn: Turn::Free,
+        examples: &["dance wildly"],
+        suggestions: None,
+        handler: |game, args| game.print(&format!("You dance {}\n", args)),
+    });
+    game.run();
+
+    let transcript = output.contents();
+    assert!(transcript.contains("Dance [Style]"));
+    assert!(transcript.contains("You dance wildly\n"));
+}
+
+#[test]
+fn test_downstream_command_takes_its_turn() {
+    let output = MemoryOutput::new();
+    let input = MemoryInput::new(["Tester", "Godmode", "Go North", "roar", "Quit"]);
+    let mut game =
+        Game::with_io(Box::new(input), Box::new(output.clone())).expect("Failed to load the world");
+    game.register_command(CommandSpec {
+        name: "Roar",
+        verbs: &["roar"],
+        argument: Argument::None,
+        help: "Roar at the enemies",
+        turn: Turn::EnemiesAttack,
+        examples: &["roar"],
+        suggestions: None,
+        handler: |game, _args| game.print("You roar\n"),
+    });
+    game.run();
+
+    let transcript = output.contents();
+    let roar = transcript.find("You roar\n").unwrap();
+    assert!(transcript[roar..].contains("the player"));
+}
+
+#[test]
+fn test_lone_direction_uses_registered_go() {
+    let output = MemoryOutput::new();
+    let input = MemoryInput::new(["Tester", "n", "Quit"]);
+    let mut game =
+        Game::with_io(Box::new(input), Box::new(output.clone())).expect("Failed to load the world");
+    game.register_command(CommandSpec {
+        name: "Go",
+        verbs: &["go"],
+        argument: Argument::Required("Direction"),
+        help: "Stay where you are",
+        turn: Turn::Free,
+        examples: &["Go North"],
+        suggestions: None,
+        handler: |game, args| game.print(&format!("You stay put instead of going {}\n", args)),
+    });
+    game.run();
+
+    let transcript = output.contents();
+    assert!(transcript.contains("You stay put instead of going n\n"));
+    assert!(!transcript.contains("Going to 2"));
+}